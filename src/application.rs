@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::{io, time};
 
 use thiserror::Error;
@@ -9,7 +8,6 @@ use crate::gfx::{Image, Renderer, TextureId};
 use crate::math::*;
 use crate::platform;
 use crate::platform::{Cursor, WindowEvent, WindowHint};
-use crate::timer::FrameTimer;
 use crate::ui::text::{FontError, FontFormat, FontId};
 use crate::ui::*;
 
@@ -24,6 +22,18 @@ pub enum Error {
     Font(#[from] FontError),
 }
 
+/// When the UI is redrawn.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Redraw {
+    /// Redraw continuously, at the target frames per second.
+    #[default]
+    Always,
+    /// Redraw only when needed, eg. when input is received, and sleep otherwise.
+    /// This uses less power, but widgets only receive [`WidgetEvent::Tick`] when
+    /// a redraw happens.
+    OnDemand,
+}
+
 #[derive(Default, Clone, Debug)]
 pub struct ImageOpts {
     /// Cursor origin.
@@ -37,17 +47,21 @@ impl ImageOpts {
     }
 }
 
-/// Application launcher.
-pub struct Application {
+/// Application launcher, for a UI with data of type `T`.
+pub struct Application<T> {
     title: String,
     graphics: Graphics,
     /// Target frames per second.
     fps: f64,
+    /// Redraw mode.
+    redraw: Redraw,
     env: Env,
+    /// External event sink.
+    sink: ExtEventSink<T>,
     cursors: Vec<(&'static str, Image, Point2D<u32>)>,
 }
 
-impl Application {
+impl<T: 'static> Application<T> {
     pub fn new(title: &str) -> Self {
         let graphics = Graphics::default();
         let env = Env::default();
@@ -55,8 +69,10 @@ impl Application {
         Self {
             title: title.to_owned(),
             fps: DEFAULT_TARGET_FPS,
+            redraw: Redraw::default(),
             graphics,
             env,
+            sink: ExtEventSink::default(),
             cursors: Vec::new(),
         }
     }
@@ -79,6 +95,18 @@ impl Application {
         self
     }
 
+    /// Set the redraw mode.
+    pub fn redraw(mut self, mode: Redraw) -> Self {
+        self.redraw = mode;
+        self
+    }
+
+    /// Get a handle for sending events to the UI from other threads,
+    /// once it is launched.
+    pub fn sink(&self) -> ExtEventSink<T> {
+        self.sink.clone()
+    }
+
     pub fn cursor(
         mut self,
         name: &'static str,
@@ -98,7 +126,7 @@ impl Application {
     }
 
     /// Launch the UI by passing in the root widget and initial data.
-    pub fn launch<T>(mut self, widget: impl Widget<T> + 'static, mut data: T) -> io::Result<()> {
+    pub fn launch(mut self, widget: impl Widget<T> + 'static, data: T) -> io::Result<()> {
         let hints = &[WindowHint::Resizable(true), WindowHint::Visible(true)];
         let (mut win, mut win_events) =
             platform::init(&self.title, 640, 480, hints, platform::GraphicsContext::Gl)?;
@@ -123,40 +151,16 @@ impl Application {
             win_size.height as f32 / ui_scale
         );
 
-        let mut renderer: gfx::backends::gl::Renderer =
-            Renderer::new(&mut win, win_size, win_scale, ui_scale)
-                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let mut renderer: gfx::backends::gl::Renderer = Renderer::new(
+            gfx::Surface::new(win_size)
+                .loader(&mut |s| win.get_proc_address(s))
+                .scale(win_scale)
+                .ui_scale(ui_scale),
+        )
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
 
-        let mut root: Pod<T, Box<dyn Widget<T>>> = Pod::new(Box::new(widget));
-        let mut store = HashMap::new();
-        let mut render_timer = FrameTimer::new();
-        let mut update_timer = FrameTimer::new();
-        let mut paint_timer = FrameTimer::new();
-        let mut events = Vec::with_capacity(16);
         let mut clock = Clock::new(time::Instant::now());
 
-        // Window state.
-        let mut resized = false;
-        let mut minimized = false;
-
-        root.lifecycle(
-            &WidgetLifecycle::Initialized(&self.graphics.textures),
-            &Context::new(Point::ORIGIN, &store),
-            &data,
-            &self.env,
-        );
-        // Initial update and layout so that the first events, eg. `CursorMove` work.
-        // If we don't do this, widget sizes will be zero when the first events land.
-        // It's important however that in the general case, update and layout are run
-        // *after* events are processed.
-        root.update(&Context::new(Point::ORIGIN, &store), &data);
-        root.layout(
-            Size::from(win.size()) / ui_scale,
-            &LayoutCtx::new(&self.graphics.fonts),
-            &data,
-            &self.env,
-        );
-
         for (name, image, origin) in self.cursors {
             if !image.rect().contains(origin) {
                 warn!("cursor '{name}' has out-of-bounds origin");
@@ -167,202 +171,216 @@ impl Application {
             self.graphics.cursors.insert(name, cursor);
         }
 
+        let waker = win_events.waker();
+        self.sink.set_waker(move || waker.wake());
+
+        // Clones of the sink may outlive the loop, and must not wake it once it's gone.
+        let _waker = ClearWaker(self.sink.clone());
+
+        let mut ui = Ui::new(widget, data)
+            .graphics(self.graphics)
+            .env(self.env)
+            .event_sink(self.sink)
+            .size(win_size)
+            .scale(ui_scale);
+
         ////////////////////////////////////////////////////////////////////////////////////////
         // Game loop
         ////////////////////////////////////////////////////////////////////////////////////////
 
+        // Time elapsed in frames that were skipped, which is added to the next update.
+        let mut skipped = time::Duration::ZERO;
+
         while win.is_open() {
-            let delta = clock.tick(self.fps);
+            let idle = self.redraw == Redraw::OnDemand;
+
+            // When redrawing on demand, sleep until something happens,
+            // or until the next timer is due.
+            if idle && !ui.needs_redraw() {
+                if let Some(timeout) = ui.timeout() {
+                    win_events.wait_timeout(timeout.saturating_sub(skipped));
+                } else {
+                    win_events.wait();
+                }
+            }
+            let delta = std::mem::take(&mut skipped) + clock.tick(self.fps);
             win_events.poll();
 
-            let cursor = Point2D::<f64>::from(win.get_cursor_pos()) / ui_scale as f64;
-            let cursor = cursor.map(|n| n.floor());
-            let win_size_logical = win.size();
-            let win_size_ui = Size::from(win_size_logical) / ui_scale;
-            let ctx = Context::new(Point::from(cursor), &store);
+            let cursor = win.get_cursor_pos();
 
             for event in win_events.flush() {
-                if event.is_input() {
-                    trace!("event: {event:?}");
-                }
-
                 match event {
-                    WindowEvent::Resized(size) => {
-                        if size.is_zero() {
-                            // On certain operating systems, the window size will be set to
-                            // zero when the window is minimized. Since a zero-sized framebuffer
-                            // is not valid, we don't render anything in this case.
-                            minimized = true;
-                        } else {
-                            minimized = false;
-                            resized = true;
-                        }
-                    }
-                    WindowEvent::CursorEntered { .. } => {
-                        // Do nothing, we generate these based on `CursorMoved`.
-                    }
-                    WindowEvent::CursorLeft { .. } => {
-                        // Do nothing, we generate these based on `CursorMoved`.
-                    }
-                    WindowEvent::Minimized => {
-                        minimized = true;
-                    }
-                    WindowEvent::Restored => {
-                        minimized = false;
-                    }
-                    WindowEvent::Focused(state) => {
-                        events.push(WidgetEvent::Focus(state));
-                    }
-                    WindowEvent::RedrawRequested => {
-                        // All events currently trigger a redraw, we don't need to
-                        // do anything special here.
-                    }
-                    WindowEvent::ScaleFactorChanged(factor) => {
-                        renderer.handle_scale_factor_changed(factor);
-                    }
-                    WindowEvent::CloseRequested => {
-                        // Ignore.
-                    }
                     WindowEvent::CursorMoved { .. } => {
                         // Nb. The position given in the event can be delayed by a frame sometimes.
                         // Therefore, we use the position gotten at the start of the render loop.
-                        events.push(WidgetEvent::MouseMove(Point::from(cursor)));
+                        ui.handle_event(WindowEvent::CursorMoved {
+                            position: platform::LogicalPosition::new(cursor.0, cursor.1),
+                        });
                     }
-                    WindowEvent::MouseInput { state, button, .. } => match state {
-                        platform::InputState::Pressed => {
-                            events.push(WidgetEvent::MouseDown(button));
-                        }
-                        platform::InputState::Released => {
-                            events.push(WidgetEvent::MouseUp(button));
-                        }
-                        _ => {}
-                    },
-                    WindowEvent::Scroll { delta, .. } => {
-                        events.push(WidgetEvent::MouseScroll(delta));
+                    // Intercept `<insert>` key for pasting.
+                    //
+                    // Reading from the clipboard causes the loop to wake up for some strange
+                    // reason I cannot comprehend. So we only read from clipboard when we
+                    // need to paste.
+                    WindowEvent::KeyboardInput(platform::KeyboardInput {
+                        key: Some(platform::Key::Insert),
+                        state: platform::InputState::Pressed,
+                        modifiers: platform::ModifiersState { shift: true, .. },
+                    }) => {
+                        ui.paste(win.clipboard());
                     }
-                    WindowEvent::KeyboardInput(input) => {
-                        // Intercept `<insert>` key for pasting.
-                        //
-                        // Reading from the clipboard causes the loop to wake up for some strange
-                        // reason I cannot comprehend. So we only read from clipboard when we
-                        // need to paste.
-                        match input {
-                            platform::KeyboardInput {
-                                key: Some(platform::Key::Insert),
-                                state: platform::InputState::Pressed,
-                                modifiers: platform::ModifiersState { shift: true, .. },
-                            } => events.push(WidgetEvent::Paste(win.clipboard())),
-
-                            platform::KeyboardInput {
-                                state,
-                                key: Some(key),
-                                modifiers,
-                            } => match state {
-                                platform::InputState::Pressed => {
-                                    events.push(WidgetEvent::KeyDown {
-                                        key,
-                                        modifiers,
-                                        repeat: false,
-                                    });
-                                }
-                                platform::InputState::Repeated => {
-                                    events.push(WidgetEvent::KeyDown {
-                                        key,
-                                        modifiers,
-                                        repeat: true,
-                                    });
-                                }
-                                platform::InputState::Released => {
-                                    events.push(WidgetEvent::KeyUp { key, modifiers });
-                                }
-                            },
-                            _ => {
-                                debug!("Ignored keyboard input with unknown key: {:?}", input);
-                            }
-                        }
-                    }
-                    WindowEvent::ReceivedCharacter(c, mods) => {
-                        events.push(WidgetEvent::CharacterReceived(c, mods));
+                    event => {
+                        ui.handle_event(event);
                     }
-                    _ => {}
-                };
+                }
             }
 
             // If minimized, don't update or render.
-            if minimized {
+            if ui.is_minimized() {
+                skipped = delta;
                 continue;
             }
-
-            // Since we may receive multiple resize events at once, instead of responded to each
-            // resize event, we handle the resize only once.
-            if resized {
-                resized = false;
-                renderer.handle_resized(win_size_logical);
-                events.push(WidgetEvent::Resized(win_size_ui));
-            }
-            root.event(&WidgetEvent::Tick(delta), &ctx, &mut data);
-
-            // A common case is that we have multiple `CursorMoved` events
-            // in one update. In that case we keep only the last one,
-            // since the in-betweens will never be seen.
-            if events.len() > 1
-                && events
-                    .iter()
-                    .all(|e| matches!(e, WidgetEvent::MouseMove(_)))
-            {
-                events.drain(..events.len() - 1);
+            // Nothing changed that would require a redraw, and no timer is due.
+            let due = ui.timeout().map_or(false, |timeout| timeout <= delta);
+            if idle && !ui.needs_redraw() && !due {
+                skipped = delta;
+                continue;
             }
+            ui.update(delta);
+            ui.paint();
+            ui.render(&mut renderer).unwrap_or_else(|err| {
+                error!("error rendering frame: {err}");
+            });
 
-            for ev in events.drain(..) {
-                root.event(&ev, &ctx, &mut data);
-            }
-            if let Some(cursor) = root.cursor() {
-                if self.graphics.cursor != Some(cursor) {
-                    if let Some(c) = self.graphics.cursors.remove(cursor) {
+            let cursor = ui.cursor();
+            let graphics = ui.graphics_mut();
+
+            if let Some(cursor) = cursor {
+                if graphics.cursor != Some(cursor) {
+                    if let Some(c) = graphics.cursors.remove(cursor) {
                         if let Some(prev) = win.set_cursor(Some(c)) {
-                            if let Some(name) = self.graphics.cursor {
-                                self.graphics.cursors.insert(name, prev);
+                            if let Some(name) = graphics.cursor {
+                                graphics.cursors.insert(name, prev);
                             }
                         }
-                        self.graphics.cursor = Some(cursor);
+                        graphics.cursor = Some(cursor);
                     }
                 }
             } else if let Some(prev) = win.set_cursor(None) {
-                if let Some(name) = self.graphics.cursor {
-                    self.graphics.cursors.insert(name, prev);
+                if let Some(name) = graphics.cursor {
+                    graphics.cursors.insert(name, prev);
                 }
-                self.graphics.cursor = None;
+                graphics.cursor = None;
             }
+            win.present();
+        }
+        Ok(())
+    }
 
-            update_timer.run(|_avg| {
-                root.update(&ctx, &data);
-                root.layout(
-                    win_size_ui,
-                    &LayoutCtx::new(&self.graphics.fonts),
-                    &data,
-                    &self.env,
-                );
-            });
+    /// Run the UI without a window, for as many frames as there are in the given script.
+    ///
+    /// Each frame, the script's window events for that frame are processed, followed by the
+    /// usual update, layout and paint passes. Frames are rendered with the software renderer,
+    /// and time advances by a fixed step of `1 / fps` per frame.
+    ///
+    /// Returns the final data and the rendered frames, one per script frame. While the
+    /// window is minimized, nothing is rendered, and the last frame rendered is repeated,
+    /// or a blank frame if there is none.
+    pub fn headless(
+        self,
+        widget: impl Widget<T> + 'static,
+        data: T,
+        script: Script,
+    ) -> io::Result<(T, Vec<Image>)> {
+        let ui_scale = DEFAULT_SCALE;
+        let delta = time::Duration::from_secs_f64(1. / self.fps);
+
+        let mut renderer = gfx::backends::software::Renderer::with_size(script.size, 1., ui_scale);
+        let mut frames = Vec::with_capacity(script.frames.len());
+        let mut ui = Ui::new(widget, data)
+            .graphics(self.graphics)
+            .env(self.env)
+            .event_sink(self.sink)
+            .size(script.size)
+            .scale(ui_scale);
+
+        for events in script.frames {
+            for event in events {
+                ui.handle_event(event);
+            }
+            if ui.is_minimized() {
+                frames.push(renderer.screen());
+                continue;
+            }
+            ui.update(delta);
+            ui.paint();
+            ui.render(&mut renderer)
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
 
-            paint_timer.run(|_avg| {
-                root.paint(
-                    Canvas::new(&ctx, &mut self.graphics, Transform::identity(), win_size_ui),
-                    &data,
-                );
-            });
+            frames.push(renderer.screen());
+        }
+        Ok((ui.into_data(), frames))
+    }
+}
 
-            render_timer.run(|_avg| {
-                renderer
-                    .frame(self.graphics.effects(), &mut store)
-                    .unwrap_or_else(|err| {
-                        error!("error rendering frame: {err}");
-                    });
+/// Clears the waker of an event sink when dropped, ie. when the event loop exits.
+struct ClearWaker<T>(ExtEventSink<T>);
 
-                root.frame(&store, &mut data);
-            });
+impl<T> Drop for ClearWaker<T> {
+    fn drop(&mut self) {
+        self.0.clear_waker();
+    }
+}
 
-            win.present();
+/// A scripted sequence of window events, used to drive a headless application.
+///
+/// ```
+/// use rgx::platform::{LogicalSize, WindowEvent, LogicalPosition};
+/// use rgx::application::Script;
+///
+/// let script = Script::new(LogicalSize::new(320., 240.))
+///     .frame([WindowEvent::CursorMoved { position: LogicalPosition::new(8., 8.) }])
+///     .idle(3);
+///
+/// assert_eq!(script.len(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Script {
+    /// Initial window size.
+    size: platform::LogicalSize,
+    /// Window events, per frame.
+    frames: Vec<Vec<WindowEvent>>,
+}
+
+impl Script {
+    /// Create a new, empty script, with the given initial window size.
+    pub fn new(size: platform::LogicalSize) -> Self {
+        Self {
+            size,
+            frames: Vec::new(),
         }
-        Ok(())
+    }
+
+    /// Add a frame, during which the given events are received.
+    pub fn frame(mut self, events: impl IntoIterator<Item = WindowEvent>) -> Self {
+        self.frames.push(events.into_iter().collect());
+        self
+    }
+
+    /// Add a number of frames during which no events are received.
+    pub fn idle(mut self, frames: usize) -> Self {
+        self.frames
+            .extend(std::iter::repeat_with(Vec::new).take(frames));
+        self
+    }
+
+    /// Number of frames in the script.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Whether the script has no frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
     }
 }
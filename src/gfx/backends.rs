@@ -1 +1,2 @@
 pub mod gl;
+pub mod software;
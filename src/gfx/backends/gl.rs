@@ -14,6 +14,7 @@ use luminance::pipeline::{PipelineError, PipelineState, TextureBinding};
 use luminance::pixel;
 use luminance::pixel::{ColorPixel, Pixel, PixelFormat, RenderablePixel};
 use luminance::render_state::RenderState;
+use luminance::scissor::ScissorRegion;
 use luminance::shader::UniformType;
 use luminance::shader::{Program, Uniform};
 use luminance::shading_gate::ShadingGate;
@@ -26,7 +27,7 @@ use crate::gfx;
 use crate::gfx::renderer::{Effect, Paint, TextureId, TextureStore};
 use crate::gfx::*;
 use crate::math::*;
-use crate::platform::{self, LogicalSize};
+use crate::platform::LogicalSize;
 
 /// GL backend.
 type Gl = gl33::GL33;
@@ -275,7 +276,8 @@ pub struct Renderer {
     pub ui_scale: f32,
 
     /// Presentation framebuffer (back buffer), this is what is rendered to screen.
-    present_fb: Framebuffer<Gl, Dim2, (), ()>,
+    /// Not set when presenting off-screen.
+    present_fb: Option<Framebuffer<Gl, Dim2, (), ()>>,
     /// Screen framebuffer, this is the virtual screen on which almost everything is
     /// drawn before it is written to the back buffer.
     screen_fb: Framebuffer<Gl, Dim2, Rgba8, pixel::Depth32F>,
@@ -304,6 +306,7 @@ impl RenderContext {
         op: RenderOp,
         identity: Transform3D,
         ortho: Transform3D,
+        scissor: Scissor,
         pipeline: &Pipeline<'_, Gl>,
         shd_gate: &mut ShadingGate<'_, Gl>,
     ) -> Result<(), Error> {
@@ -312,15 +315,19 @@ impl RenderContext {
                 tess,
                 transform,
                 blending,
+                clip,
             } => {
+                let render_st = self
+                    .render_st
+                    .clone()
+                    .set_blending(blending)
+                    .set_scissor(clip.map(|c| scissor.region(c)));
+
                 shd_gate.shade(&mut self.shape2d, |mut iface, uni, mut rdr_gate| {
                     iface.set(&uni.ortho, ortho);
                     iface.set(&uni.transform, identity * transform);
 
-                    rdr_gate.render(
-                        &self.render_st.clone().set_blending(blending),
-                        |mut tess_gate| tess_gate.render(&tess),
-                    )?;
+                    rdr_gate.render(&render_st, |mut tess_gate| tess_gate.render(&tess))?;
 
                     Ok::<_, PipelineError>(())
                 })?;
@@ -330,7 +337,14 @@ impl RenderContext {
                 transform,
                 texture,
                 blending,
+                clip,
             } => {
+                let render_st = self
+                    .render_st
+                    .clone()
+                    .set_blending(blending)
+                    .set_scissor(clip.map(|c| scissor.region(c)));
+
                 let texture = if let Some(texture) = self.textures.storage.get_mut(&texture) {
                     texture
                 } else if let Some(target) = self.targets.get_mut(&texture) {
@@ -346,10 +360,7 @@ impl RenderContext {
                     iface.set(&uni.transform, identity * transform);
                     iface.set(&uni.tex, bound.binding());
 
-                    rdr_gate.render(
-                        &self.render_st.clone().set_blending(blending),
-                        |mut tess_gate| tess_gate.render(&tess),
-                    )?;
+                    rdr_gate.render(&render_st, |mut tess_gate| tess_gate.render(&tess))?;
 
                     Ok::<_, PipelineError>(())
                 })?;
@@ -359,6 +370,37 @@ impl RenderContext {
     }
 }
 
+/// Maps clipping rectangles to scissor regions of a framebuffer.
+#[derive(Debug, Copy, Clone)]
+struct Scissor {
+    /// Pixels per unit.
+    scale: f32,
+    /// Height of the framebuffer in pixels, if its y axis points down. Scissor
+    /// regions always have their origin at the bottom left.
+    flip: Option<u32>,
+}
+
+impl Scissor {
+    fn region(&self, clip: Rect<f32>) -> ScissorRegion {
+        let (min, max) = (clip.min(), clip.max());
+        let x0 = (min.x * self.scale).round().max(0.) as u32;
+        let x1 = (max.x * self.scale).round().max(0.) as u32;
+        let y0 = (min.y * self.scale).round().max(0.) as u32;
+        let y1 = (max.y * self.scale).round().max(0.) as u32;
+        let (y0, y1) = match self.flip {
+            Some(h) => (h.saturating_sub(y1), h.saturating_sub(y0)),
+            None => (y0, y1),
+        };
+
+        ScissorRegion {
+            x: x0,
+            y: y0,
+            width: x1.saturating_sub(x0),
+            height: y1.saturating_sub(y0),
+        }
+    }
+}
+
 /// Graphics backend.
 struct Backend {
     gl: Gl,
@@ -427,6 +469,8 @@ pub enum Error {
     Io(#[from] io::Error),
     #[error("program error: {0}")]
     Program(#[from] luminance::shader::ProgramError),
+    #[error("a function loader is required to initialize the GL backend")]
+    MissingLoader,
     #[error("error: {0}")]
     Custom(&'static str),
 }
@@ -438,6 +482,7 @@ enum RenderOp {
         tess: Tess<Gl, Shape2dVertex>,
         transform: Transform3D,
         blending: Blending,
+        clip: Option<Rect<f32>>,
     },
     /// Draw a sprite.
     Sprite {
@@ -445,6 +490,7 @@ enum RenderOp {
         transform: Transform3D,
         texture: TextureId,
         blending: Blending,
+        clip: Option<Rect<f32>>,
     },
 }
 
@@ -473,8 +519,12 @@ impl Frame {
 
         for eff in effects {
             match eff {
-                Effect::Paint { paint, blending } => {
-                    frame.paint(paint, blending.into(), backend)?;
+                Effect::Paint {
+                    paint,
+                    blending,
+                    clip,
+                } => {
+                    frame.paint(paint, blending.into(), clip, backend)?;
                 }
                 Effect::Clear { id, color } => {
                     frame.clear(id, color);
@@ -555,6 +605,7 @@ impl Frame {
         &mut self,
         paint: Paint,
         blending: Blending,
+        clip: Option<Rect<f32>>,
         backend: &mut Backend,
     ) -> Result<(), Error> {
         match paint {
@@ -575,6 +626,7 @@ impl Frame {
                     tess,
                     transform: transform.into(),
                     blending,
+                    clip,
                 });
             }
 
@@ -597,6 +649,7 @@ impl Frame {
                     transform: transform.into(),
                     texture,
                     blending,
+                    clip,
                 });
             }
 
@@ -610,13 +663,17 @@ impl Frame {
 impl gfx::Renderer for Renderer {
     type Error = Error;
 
-    fn new(
-        win: &mut platform::backend::Window,
-        win_size: LogicalSize,
-        win_scale: f64,
-        ui_scale: f32,
-    ) -> Result<Self, Error> {
-        gl::load_with(|s| win.get_proc_address(s) as *const _);
+    fn new(surface: Surface<'_>) -> Result<Self, Error> {
+        let Surface {
+            loader,
+            size: win_size,
+            scale: win_scale,
+            ui_scale,
+            present,
+        } = surface;
+        let loader = loader.ok_or(Error::MissingLoader)?;
+
+        gl::load_with(|s| loader(s) as *const _);
 
         let gl = Gl::new()?;
         let mut backend = Backend { gl };
@@ -634,11 +691,17 @@ impl gfx::Renderer for Renderer {
             include_str!("gl/data/screen.frag"),
         )?;
 
-        let physical = win_size.to_physical(win_scale);
-        let present_fb = Framebuffer::back_buffer(
-            &mut backend,
-            [physical.width as u32, physical.height as u32],
-        )?;
+        let present_fb = match present {
+            Present::BackBuffer => {
+                let physical = win_size.to_physical(win_scale);
+
+                Some(Framebuffer::back_buffer(
+                    &mut backend,
+                    [physical.width as u32, physical.height as u32],
+                )?)
+            }
+            Present::Offscreen => None,
+        };
         let screen_fb = Framebuffer::new(
             &mut backend,
             [win_size.width as u32, win_size.height as u32],
@@ -726,6 +789,10 @@ impl gfx::Renderer for Renderer {
                             op,
                             Transform3D::identity(),
                             ortho,
+                            Scissor {
+                                scale: 1.,
+                                flip: None,
+                            },
                             &pipeline,
                             &mut shd_gate,
                         )?;
@@ -747,6 +814,10 @@ impl gfx::Renderer for Renderer {
             self.win_scale as f32 * self.ui_scale,
             0.,
         );
+        let scissor = Scissor {
+            scale: self.win_scale as f32 * self.ui_scale,
+            flip: Some(self.screen_fb.size()[1]),
+        };
 
         builder.pipeline(
             &self.screen_fb,
@@ -754,7 +825,7 @@ impl gfx::Renderer for Renderer {
             |pipeline, mut shd_gate| {
                 for op in frame.onscreen {
                     self.context
-                        .render(op, identity, ortho, &pipeline, &mut shd_gate)?;
+                        .render(op, identity, ortho, scissor, &pipeline, &mut shd_gate)?;
                 }
                 Ok::<_, Error>(())
             },
@@ -764,24 +835,26 @@ impl gfx::Renderer for Renderer {
         // Render to back buffer.                                             //
         ////////////////////////////////////////////////////////////////////////
 
-        builder.pipeline::<PipelineError, _, _, _, _>(
-            &self.present_fb,
-            &self.pipeline_st,
-            |pipeline, mut shd_gate| {
-                // Render screen framebuffer.
-                let bound_screen = pipeline.bind_texture(self.screen_fb.color_slot())?;
-                shd_gate.shade(
-                    &mut self.context.screen2d,
-                    |mut iface, uni, mut rdr_gate| {
-                        iface.set(&uni.framebuffer, bound_screen.binding());
-                        rdr_gate.render(&self.context.render_st, |mut tess_gate| {
-                            tess_gate.render(&screen_tess)
-                        })
-                    },
-                )?;
-                Ok(())
-            },
-        );
+        if let Some(present_fb) = &self.present_fb {
+            builder.pipeline::<PipelineError, _, _, _, _>(
+                present_fb,
+                &self.pipeline_st,
+                |pipeline, mut shd_gate| {
+                    // Render screen framebuffer.
+                    let bound_screen = pipeline.bind_texture(self.screen_fb.color_slot())?;
+                    shd_gate.shade(
+                        &mut self.context.screen2d,
+                        |mut iface, uni, mut rdr_gate| {
+                            iface.set(&uni.framebuffer, bound_screen.binding());
+                            rdr_gate.render(&self.context.render_st, |mut tess_gate| {
+                                tess_gate.render(&screen_tess)
+                            })
+                        },
+                    )?;
+                    Ok(())
+                },
+            );
+        }
 
         ////////////////////////////////////////////////////////////////////////
         // Read modified framebuffer textures into host memory.               //
@@ -805,21 +878,41 @@ impl gfx::Renderer for Renderer {
         self.win_scale = win_scale;
         self.handle_resized(self.win_size);
     }
-}
-
-impl Renderer {
-    pub fn handle_resized(&mut self, size: platform::LogicalSize) {
-        let physical = size.to_physical(self.win_scale);
-
-        self.present_fb = Framebuffer::back_buffer(
-            &mut self.backend,
-            [physical.width as u32, physical.height as u32],
-        )
-        .unwrap();
 
+    fn handle_resized(&mut self, size: LogicalSize) {
+        if self.present_fb.is_some() {
+            let physical = size.to_physical(self.win_scale);
+
+            self.present_fb = Some(
+                Framebuffer::back_buffer(
+                    &mut self.backend,
+                    [physical.width as u32, physical.height as u32],
+                )
+                .unwrap(),
+            );
+        }
         self.win_size = size;
         self.handle_scale_changed(self.win_scale);
     }
+}
+
+impl Renderer {
+    /// Read back the contents of the screen framebuffer, as of the last frame.
+    pub fn screen(&mut self) -> Result<Image, Error> {
+        let [w, h] = self.screen_fb.size();
+        let texels = self.screen_fb.color_slot().get_raw_texels()?;
+        let texels = Rgba8::align(&texels);
+
+        // Rows are stored bottom-up.
+        let pixels = texels
+            .chunks_exact(w as usize)
+            .rev()
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+
+        Ok(Image::new(pixels, [w, h]))
+    }
 
     pub fn handle_scale_changed(&mut self, scale: f64) {
         self.screen_fb = Framebuffer::new(
@@ -0,0 +1,797 @@
+//! A 2D software renderer.
+//!
+//! Rasterizes the same effects as the GL backend, on the CPU. Since it doesn't need a GPU
+//! and its output is deterministic, it's well suited for headless environments and tests.
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, OnceLock};
+
+use crate::gfx;
+use crate::gfx::renderer::{Effect, Paint, TextureId, TextureStore};
+use crate::gfx::*;
+use crate::math::*;
+use crate::platform::LogicalSize;
+
+/// Renderer error.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{0} not found")]
+    TextureNotFound(TextureId),
+}
+
+/// A color buffer with an associated depth buffer.
+#[derive(Debug, Clone)]
+struct Framebuffer {
+    /// Size of the framebuffer in pixels.
+    size: Size<u32>,
+    /// Color buffer, in sRGB space.
+    pixels: Vec<Rgba8>,
+    /// Depth buffer.
+    depth: Vec<f32>,
+}
+
+impl Framebuffer {
+    /// Create a new framebuffer from existing texels.
+    fn new(size: Size<u32>, texels: &[Rgba8]) -> Self {
+        assert_eq!(size.area() as usize, texels.len());
+
+        Self {
+            size,
+            pixels: texels.to_vec(),
+            depth: vec![1.; texels.len()],
+        }
+    }
+
+    /// Create a new transparent framebuffer.
+    fn blank(size: Size<u32>) -> Self {
+        Self::new(size, &vec![Rgba8::TRANSPARENT; size.area() as usize])
+    }
+
+    fn clear(&mut self, color: Rgba8) {
+        self.pixels.fill(color);
+    }
+
+    fn clear_depth(&mut self) {
+        self.depth.fill(1.);
+    }
+
+    fn upload(&mut self, texels: &[Rgba8]) {
+        if texels.len() == self.pixels.len() {
+            self.pixels.copy_from_slice(texels);
+        } else {
+            warn!(
+                "ignoring upload of {} texels to a {}x{} target",
+                texels.len(),
+                self.size.w,
+                self.size.h
+            );
+        }
+    }
+
+    /// Return a resized framebuffer. Existing pixels are transferred in the same way as
+    /// they are with the GL backend: anchored to the last rows of the new framebuffer.
+    fn resized(self, size: Size<u32>) -> Self {
+        let mut resized = Self::blank(size);
+        let tw = u32::min(size.w, self.size.w) as usize;
+        let th = u32::min(size.h, self.size.h) as usize;
+        let oy = (size.h as usize) - th;
+
+        for y in 0..th {
+            let src = y * self.size.w as usize;
+            let dst = (oy + y) * size.w as usize;
+
+            resized.pixels[dst..dst + tw].copy_from_slice(&self.pixels[src..src + tw]);
+        }
+        resized
+    }
+
+    fn image(&self) -> Image {
+        Image::new(self.pixels.clone(), self.size)
+    }
+
+    /// Write a fragment, if it passes the depth test.
+    fn fragment(&mut self, x: usize, y: usize, depth: f32, color: [f32; 4], blending: Blending) {
+        let i = y * self.size.w as usize + x;
+
+        if depth > self.depth[i] {
+            return;
+        }
+        self.depth[i] = depth;
+
+        let [r, g, b, a] = color;
+        let out = match blending {
+            Blending::Alpha => {
+                let dst = self.pixels[i];
+                let inv = 1. - a;
+
+                [
+                    r * a + linearize(dst.r) * inv,
+                    g * a + linearize(dst.g) * inv,
+                    b * a + linearize(dst.b) * inv,
+                    a * a + (dst.a as f32 / 255.) * inv,
+                ]
+            }
+            Blending::Constant => color,
+        };
+        self.pixels[i] = Rgba8::new(
+            delinearize(out[0]),
+            delinearize(out[1]),
+            delinearize(out[2]),
+            (out[3].clamp(0., 1.) * 255.).round() as u8,
+        );
+    }
+}
+
+/// Software 2D renderer.
+pub struct Renderer {
+    /// Window size in logical pixels.
+    pub win_size: LogicalSize,
+    /// Window/device scale.
+    pub win_scale: f64,
+    /// UI scale (user-defined).
+    pub ui_scale: f32,
+
+    /// Screen framebuffer, this is the virtual screen on which everything
+    /// that isn't rendered off-screen is drawn.
+    screen: Framebuffer,
+    /// Textures loaded with [`Effect::Texture`].
+    textures: HashMap<TextureId, Image>,
+    /// Off-screen render targets.
+    targets: HashMap<TextureId, Framebuffer>,
+}
+
+impl Renderer {
+    /// Create a renderer that isn't attached to any window.
+    pub fn with_size(win_size: LogicalSize, win_scale: f64, ui_scale: f32) -> Self {
+        Self {
+            win_size,
+            win_scale,
+            ui_scale,
+            screen: Framebuffer::blank(Self::screen_size(win_size, win_scale)),
+            textures: HashMap::new(),
+            targets: HashMap::new(),
+        }
+    }
+
+    /// Get the contents of the screen framebuffer, as of the last frame.
+    pub fn screen(&self) -> Image {
+        self.screen.image()
+    }
+
+    /// Size of the screen framebuffer in pixels, given the window size and scale.
+    /// Frames are rendered at the window scale, so this is the physical window size.
+    fn screen_size(size: LogicalSize, win_scale: f64) -> Size<u32> {
+        Size::new(
+            (size.width * win_scale).round() as u32,
+            (size.height * win_scale).round() as u32,
+        )
+    }
+}
+
+impl gfx::Renderer for Renderer {
+    type Error = Error;
+
+    /// Create a new renderer. Since frames are never presented, the surface's
+    /// function loader and presentation target are ignored.
+    fn new(surface: Surface<'_>) -> Result<Self, Error> {
+        Ok(Self::with_size(
+            surface.size,
+            surface.scale,
+            surface.ui_scale,
+        ))
+    }
+
+    fn frame<E, T>(&mut self, effects: E, store: &mut T) -> Result<(), Error>
+    where
+        E: Iterator<Item = Effect>,
+        T: TextureStore,
+    {
+        let frame = Frame::new(effects, self);
+
+        ////////////////////////////////////////////////////////////////////////
+        // Render to offscreen textures.
+        ////////////////////////////////////////////////////////////////////////
+
+        for (id, ops) in frame.offscreen {
+            // Temporarily remove the target from the map to be able to render to it,
+            // while sampling from the other targets.
+            let mut target = if let Some(t) = self.targets.remove(&id) {
+                t
+            } else {
+                continue;
+            };
+            let clear = frame.clear.get(&id);
+
+            if let Some(texels) = frame.upload.get(&id) {
+                target.upload(texels);
+
+                if clear.is_some() {
+                    warn!("ignoring `clear` operation due to `upload` on {}", id);
+                }
+                if !ops.is_empty() {
+                    warn!("ignoring paint operations due to `upload` on {}", id);
+                }
+            } else {
+                if let Some(color) = clear {
+                    target.clear(*color);
+                }
+                target.clear_depth();
+
+                let result = ops
+                    .iter()
+                    .try_for_each(|op| op.render(&mut target, 1., &self.textures, &self.targets));
+
+                // Return the target to the map before bailing.
+                if let Err(err) = result {
+                    self.targets.insert(id, target);
+                    return Err(err);
+                }
+            }
+            self.targets.insert(id, target);
+        }
+
+        ////////////////////////////////////////////////////////////////////////
+        // Render to screen framebuffer.
+        ////////////////////////////////////////////////////////////////////////
+
+        let scale = self.win_scale as f32 * self.ui_scale;
+
+        self.screen.clear(Rgba8::TRANSPARENT);
+        self.screen.clear_depth();
+
+        for op in &frame.onscreen {
+            op.render(&mut self.screen, scale, &self.textures, &self.targets)?;
+        }
+
+        ////////////////////////////////////////////////////////////////////////
+        // Copy modified framebuffer textures into the store.
+        ////////////////////////////////////////////////////////////////////////
+
+        for target in &frame.dirty {
+            if let Some(fb) = self.targets.get(target) {
+                store.put(*target, fb.image());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn scale(&mut self, factor: f32) -> f32 {
+        self.ui_scale *= factor;
+        self.ui_scale
+    }
+
+    fn handle_scale_factor_changed(&mut self, win_scale: f64) {
+        self.win_scale = win_scale;
+        self.handle_resized(self.win_size);
+    }
+
+    fn handle_resized(&mut self, size: LogicalSize) {
+        self.win_size = size;
+        self.screen = Framebuffer::blank(Self::screen_size(size, self.win_scale));
+    }
+}
+
+#[derive(Debug)]
+enum RenderOp {
+    /// Draw a shape.
+    Shape {
+        vertices: Vec<shape2d::Vertex>,
+        transform: Transform,
+        blending: Blending,
+        clip: Option<Rect<f32>>,
+    },
+    /// Draw a sprite.
+    Sprite {
+        vertices: Vec<sprite2d::Vertex>,
+        transform: Transform,
+        texture: TextureId,
+        blending: Blending,
+        clip: Option<Rect<f32>>,
+    },
+}
+
+impl RenderOp {
+    /// Rasterize the operation onto a framebuffer.
+    fn render(
+        &self,
+        fb: &mut Framebuffer,
+        scale: f32,
+        textures: &HashMap<TextureId, Image>,
+        targets: &HashMap<TextureId, Framebuffer>,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Shape {
+                vertices,
+                transform,
+                blending,
+                clip,
+            } => {
+                let clip = clip.map(|c| c.map(|n| n * scale));
+                let transform = *transform * Transform::scale(scale);
+
+                for tri in vertices.chunks_exact(3) {
+                    let points = [tri[0], tri[1], tri[2]].map(|v| {
+                        let center = Point::new(v.center.x, v.center.y);
+                        let p = rotate(Point::new(v.position.x, v.position.y), center, v.angle);
+
+                        (transform * p, v.position.z)
+                    });
+                    let colors = [tri[0], tri[1], tri[2]].map(|v| linear(v.color));
+
+                    rasterize(fb, clip, points, |[l0, l1, l2]| {
+                        let mut color = [0.; 4];
+                        for (i, c) in color.iter_mut().enumerate() {
+                            *c = colors[0][i] * l0 + colors[1][i] * l1 + colors[2][i] * l2;
+                        }
+                        (color, *blending)
+                    });
+                }
+            }
+            Self::Sprite {
+                vertices,
+                transform,
+                texture,
+                blending,
+                clip,
+            } => {
+                let clip = clip.map(|c| c.map(|n| n * scale));
+                let (size, texels) = if let Some(image) = textures.get(texture) {
+                    (image.size, &*image.pixels)
+                } else if let Some(target) = targets.get(texture) {
+                    (target.size, target.pixels.as_slice())
+                } else {
+                    return Err(Error::TextureNotFound(*texture));
+                };
+                let transform = *transform * Transform::scale(scale);
+
+                for tri in vertices.chunks_exact(3) {
+                    let points = [tri[0], tri[1], tri[2]].map(|v| {
+                        (
+                            transform * Point::new(v.position.x, v.position.y),
+                            v.position.z,
+                        )
+                    });
+
+                    rasterize(fb, clip, points, |[l0, l1, l2]| {
+                        let v = [tri[0], tri[1], tri[2]];
+                        let u = v[0].uv.x * l0 + v[1].uv.x * l1 + v[2].uv.x * l2;
+                        let w = v[0].uv.y * l0 + v[1].uv.y * l1 + v[2].uv.y * l2;
+                        let opacity = v[0].opacity * l0 + v[1].opacity * l1 + v[2].opacity * l2;
+                        let tint = linear(v[0].color);
+                        let texel = linear(sample(texels, size, u, w));
+                        let mix = |t: f32, c: f32| t * (1. - tint[3]) + c * tint[3];
+
+                        (
+                            [
+                                mix(texel[0], tint[0]),
+                                mix(texel[1], tint[1]),
+                                mix(texel[2], tint[2]),
+                                texel[3] * opacity,
+                            ],
+                            *blending,
+                        )
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Render frame.
+#[derive(Default)]
+struct Frame {
+    /// On-screen operations.
+    onscreen: Vec<RenderOp>,
+    /// Off-screen operations, ordered by target for deterministic output.
+    offscreen: BTreeMap<TextureId, Vec<RenderOp>>,
+    /// Textures to clear.
+    clear: HashMap<TextureId, Rgba8>,
+    /// Textures to upload.
+    upload: HashMap<TextureId, Arc<[Rgba8]>>,
+    /// Textures that were modified.
+    dirty: HashSet<TextureId>,
+}
+
+impl Frame {
+    fn new(effects: impl Iterator<Item = Effect>, renderer: &mut Renderer) -> Self {
+        let mut frame = Frame::default();
+
+        for eff in effects {
+            match eff {
+                Effect::Paint {
+                    paint,
+                    blending,
+                    clip,
+                } => {
+                    frame.paint(paint, blending, clip);
+                }
+                Effect::Clear { id, color } => {
+                    frame.clear.insert(id, color);
+                    frame.offscreen.entry(id).or_default();
+                }
+                Effect::Texture {
+                    id,
+                    image,
+                    offscreen,
+                } => {
+                    if offscreen {
+                        renderer
+                            .targets
+                            .entry(id)
+                            .or_insert_with(|| Framebuffer::new(image.size, &image.pixels));
+                        frame.offscreen.entry(id).or_default();
+                    } else {
+                        renderer.textures.entry(id).or_insert(image);
+                    }
+                }
+                Effect::Resize { id, size } => {
+                    if let Some(fb) = renderer.targets.remove(&id) {
+                        renderer.targets.insert(id, fb.resized(size));
+                    }
+                }
+                Effect::Upload { id, texels } => {
+                    frame.upload.insert(id, texels);
+                    frame.offscreen.entry(id).or_default();
+                }
+            }
+        }
+        frame
+    }
+
+    fn paint(&mut self, paint: Paint, blending: Blending, clip: Option<Rect<f32>>) {
+        let (op, target) = match paint {
+            Paint::Shape {
+                transform,
+                vertices,
+                target,
+            } if !vertices.is_empty() => (
+                RenderOp::Shape {
+                    vertices,
+                    transform,
+                    blending,
+                    clip,
+                },
+                target,
+            ),
+            Paint::Sprite {
+                transform,
+                vertices,
+                texture,
+                target,
+            } if !vertices.is_empty() => (
+                RenderOp::Sprite {
+                    vertices,
+                    transform,
+                    texture,
+                    blending,
+                    clip,
+                },
+                target,
+            ),
+            _ => return,
+        };
+
+        if let Some(target) = target {
+            self.dirty.insert(target);
+            self.offscreen.entry(target).or_default().push(op);
+        } else {
+            self.onscreen.push(op);
+        }
+    }
+}
+
+/// Rasterize a triangle given in pixel coordinates, with the z component of each vertex.
+/// The shader is given the barycentric coordinates of the fragment and returns its
+/// linear color.
+///
+/// Pixels are sampled at their center, and edges shared by two triangles are only
+/// drawn once, using a top-left fill rule. Pixels outside of the clipping rectangle,
+/// given in pixel coordinates, aren't drawn.
+fn rasterize(
+    fb: &mut Framebuffer,
+    clip: Option<Rect<f32>>,
+    mut points: [(Point, f32); 3],
+    shader: impl Fn([f32; 3]) -> ([f32; 4], Blending),
+) {
+    let edge = |a: Point, b: Point, p: Point| (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x);
+    let mut area = edge(points[0].0, points[1].0, points[2].0);
+
+    if area == 0. || !area.is_finite() {
+        return;
+    }
+    let flipped = area < 0.;
+    if flipped {
+        points.swap(1, 2);
+        area = -area;
+    }
+    let [(p0, z0), (p1, z1), (p2, z2)] = points;
+
+    // An edge owns the pixels that lie exactly on it if it's a top or left edge.
+    // Since triangles sharing an edge traverse it in opposite directions, only one
+    // of them will draw those pixels.
+    let owns = |a: Point, b: Point| {
+        let d = b - a;
+        d.y > 0. || (d.y == 0. && d.x < 0.)
+    };
+    let inside = |w: f32, owned: bool| w > 0. || (w == 0. && owned);
+    let (o0, o1, o2) = (owns(p1, p2), owns(p2, p0), owns(p0, p1));
+
+    // Clip rectangles are rounded to whole pixels, like scissor rectangles.
+    let clip = clip.unwrap_or(Rect::origin(fb.size.map(|n| n as f32)));
+    let (clip_min, clip_max) = (clip.min(), clip.max());
+
+    let min_x =
+        p0.x.min(p1.x)
+            .min(p2.x)
+            .floor()
+            .max(clip_min.x.round())
+            .max(0.) as usize;
+    let min_y =
+        p0.y.min(p1.y)
+            .min(p2.y)
+            .floor()
+            .max(clip_min.y.round())
+            .max(0.) as usize;
+    let max_x = (p0
+        .x
+        .max(p1.x)
+        .max(p2.x)
+        .ceil()
+        .min(clip_max.x.round())
+        .max(0.) as usize)
+        .min(fb.size.w as usize);
+    let max_y = (p0
+        .y
+        .max(p1.y)
+        .max(p2.y)
+        .ceil()
+        .min(clip_max.y.round())
+        .max(0.) as usize)
+        .min(fb.size.h as usize);
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let p = Point::new(x as f32 + 0.5, y as f32 + 0.5);
+            let w0 = edge(p1, p2, p);
+            let w1 = edge(p2, p0, p);
+            let w2 = edge(p0, p1, p);
+
+            if !(inside(w0, o0) && inside(w1, o1) && inside(w2, o2)) {
+                continue;
+            }
+            let (l0, l1, l2) = (w0 / area, w1 / area, w2 / area);
+            let z = z0 * l0 + z1 * l1 + z2 * l2;
+            // Undo the vertex swap, so that the shader sees the original vertex order.
+            let weights = if flipped { [l0, l2, l1] } else { [l0, l1, l2] };
+            let (color, blending) = shader(weights);
+
+            // This is the depth produced by the orthographic projection
+            // used by the GL backend.
+            fb.fragment(x, y, (1. - z) / 2., color, blending);
+        }
+    }
+}
+
+/// Rotate a point around a center. Matches the rotation done in the shape shader.
+fn rotate(p: Point, center: Point, angle: f32) -> Point {
+    if angle == 0. {
+        return p;
+    }
+    let (s, c) = angle.sin_cos();
+    let d = p - center;
+
+    Point::new(c * d.x + s * d.y + center.x, -s * d.x + c * d.y + center.y)
+}
+
+/// Sample a texture with nearest filtering and repeat wrapping.
+fn sample(texels: &[Rgba8], size: Size<u32>, u: f32, v: f32) -> Rgba8 {
+    if size.is_zero() {
+        return Rgba8::TRANSPARENT;
+    }
+    let x = ((u * size.w as f32).floor() as i64).rem_euclid(size.w as i64) as usize;
+    let y = ((v * size.h as f32).floor() as i64).rem_euclid(size.h as i64) as usize;
+
+    texels[y * size.w as usize + x]
+}
+
+/// Convert a color to linear space, with normalized channels.
+fn linear(color: Rgba8) -> [f32; 4] {
+    [
+        linearize(color.r),
+        linearize(color.g),
+        linearize(color.b),
+        color.a as f32 / 255.,
+    ]
+}
+
+/// Convert an sRGB channel to linear space.
+fn linearize(c: u8) -> f32 {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        let mut table = [0.; 256];
+        for (i, v) in table.iter_mut().enumerate() {
+            let c = i as f32 / 255.;
+
+            *v = if c < 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            };
+        }
+        table
+    })[c as usize]
+}
+
+/// Convert a linear channel to sRGB space.
+fn delinearize(c: f32) -> u8 {
+    let c = c.clamp(0., 1.);
+    let s = if c < 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1. / 2.4) - 0.055
+    };
+    (s * 255.).round() as u8
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gfx::Renderer as _;
+
+    fn shape(shape: impl Shape, target: Option<TextureId>) -> Effect {
+        Effect::from(Paint::Shape {
+            transform: Transform::identity(),
+            vertices: shape.vertices(),
+            target,
+        })
+    }
+
+    #[test]
+    fn test_srgb_roundtrip() {
+        for c in 0..=255 {
+            assert_eq!(delinearize(linearize(c)), c);
+        }
+    }
+
+    #[test]
+    fn test_shapes() {
+        let mut renderer = Renderer::with_size(LogicalSize::new(8., 8.), 1., 2.);
+        let mut store = HashMap::new();
+        let effects = vec![
+            shape(Rectangle::new([0., 0.], [2., 2.]).fill(Rgba8::RED), None),
+            shape(
+                Rectangle::new([1., 1.], [2., 2.]).fill(Rgba8::BLUE.alpha(0x80)),
+                None,
+            ),
+        ];
+        renderer.frame(effects.into_iter(), &mut store).unwrap();
+
+        let screen = renderer.screen();
+        let red = Rgba8::RED;
+        let blank = Rgba8::TRANSPARENT;
+
+        assert_eq!(screen.size, Size::new(8, 8));
+        assert_eq!(screen.sample(Point2D::new(0, 0)), Some(&red));
+        assert_eq!(screen.sample(Point2D::new(3, 0)), Some(&red));
+        assert_eq!(screen.sample(Point2D::new(4, 0)), Some(&blank));
+        // Like with GL, the alpha channel is blended with the same factors as the color.
+        assert_eq!(screen.sample(Point2D::new(5, 5)).map(|c| c.a), Some(0x40));
+        assert_eq!(screen.sample(Point2D::new(6, 6)), Some(&blank));
+
+        // Where both shapes overlap, the blue is blended over the red.
+        let blended = screen.sample(Point2D::new(3, 3)).unwrap();
+        assert!(blended.r > 0 && blended.r < red.r);
+        assert!(blended.b > 0);
+        assert_eq!(blended.a, 0xbf);
+    }
+
+    #[test]
+    fn test_win_scale() {
+        let mut renderer = Renderer::with_size(LogicalSize::new(8., 4.), 2., 1.);
+        let mut store = HashMap::new();
+        let effects = vec![shape(
+            Rectangle::new([0., 0.], [8., 4.]).fill(Rgba8::RED),
+            None,
+        )];
+        renderer.frame(effects.into_iter(), &mut store).unwrap();
+
+        // The screen is the physical size of the window, and is entirely covered.
+        let screen = renderer.screen();
+
+        assert_eq!(screen.size, Size::new(16, 8));
+        assert_eq!(screen.sample(Point2D::new(0, 0)), Some(&Rgba8::RED));
+        assert_eq!(screen.sample(Point2D::new(15, 7)), Some(&Rgba8::RED));
+
+        // Changing the scale factor resizes the screen.
+        renderer.handle_scale_factor_changed(1.5);
+        renderer.frame(std::iter::empty(), &mut store).unwrap();
+
+        assert_eq!(renderer.screen().size, Size::new(12, 6));
+    }
+
+    #[test]
+    fn test_clip() {
+        let mut renderer = Renderer::with_size(LogicalSize::new(8., 8.), 1., 2.);
+        let mut store = HashMap::new();
+        let effects = vec![Effect::Paint {
+            paint: Paint::from(Rectangle::new([0., 0.], [4., 4.]).fill(Rgba8::RED)),
+            blending: Blending::default(),
+            clip: Some(Rect::new([1., 1.], [2., 1.])),
+        }];
+        renderer.frame(effects.into_iter(), &mut store).unwrap();
+
+        let screen = renderer.screen();
+        let red = Rgba8::RED;
+        let blank = Rgba8::TRANSPARENT;
+
+        // The clip is scaled along with the paint.
+        assert_eq!(screen.sample(Point2D::new(2, 2)), Some(&red));
+        assert_eq!(screen.sample(Point2D::new(5, 3)), Some(&red));
+        assert_eq!(screen.sample(Point2D::new(1, 2)), Some(&blank));
+        assert_eq!(screen.sample(Point2D::new(2, 1)), Some(&blank));
+        assert_eq!(screen.sample(Point2D::new(6, 2)), Some(&blank));
+        assert_eq!(screen.sample(Point2D::new(2, 4)), Some(&blank));
+    }
+
+    #[test]
+    fn test_offscreen() {
+        let mut renderer = Renderer::with_size(LogicalSize::new(8., 8.), 1., 1.);
+        let mut store = HashMap::new();
+        let target = TextureId::next();
+        let effects = vec![
+            Effect::Texture {
+                id: target,
+                image: Image::blank([4, 4]),
+                offscreen: true,
+            },
+            Effect::Clear {
+                id: target,
+                color: Rgba8::BLACK,
+            },
+            shape(
+                Rectangle::new([0., 0.], [2., 1.]).fill(Rgba8::WHITE),
+                Some(target),
+            ),
+        ];
+        renderer.frame(effects.into_iter(), &mut store).unwrap();
+
+        let image = store.get(&target).unwrap();
+
+        assert_eq!(image.sample(Point2D::new(0, 0)), Some(&Rgba8::WHITE));
+        assert_eq!(image.sample(Point2D::new(1, 0)), Some(&Rgba8::WHITE));
+        assert_eq!(image.sample(Point2D::new(2, 0)), Some(&Rgba8::BLACK));
+        assert_eq!(image.sample(Point2D::new(0, 1)), Some(&Rgba8::BLACK));
+        // Nothing was drawn to the screen.
+        assert!(renderer
+            .screen()
+            .pixels
+            .iter()
+            .all(|p| *p == Rgba8::TRANSPARENT));
+
+        // Textures that are drawn from are sampled from.
+        let effects = vec![Effect::from(Paint::Sprite {
+            transform: Transform::identity(),
+            vertices: sprite2d::Batch::new([4, 4])
+                .item(
+                    Rect::origin(Size::new(4., 4.)),
+                    Rect::origin(Size::new(4., 4.)),
+                    ZDepth::default(),
+                    Rgba::TRANSPARENT,
+                    1.,
+                    Repeat::default(),
+                )
+                .vertices(),
+            texture: target,
+            target: None,
+        })];
+        renderer.frame(effects.into_iter(), &mut store).unwrap();
+
+        let screen = renderer.screen();
+
+        assert_eq!(screen.sample(Point2D::new(1, 0)), Some(&Rgba8::WHITE));
+        assert_eq!(screen.sample(Point2D::new(3, 3)), Some(&Rgba8::BLACK));
+        assert_eq!(screen.sample(Point2D::new(4, 4)), Some(&Rgba8::TRANSPARENT));
+    }
+}
@@ -1,11 +1,11 @@
 use std::collections::HashMap;
-use std::fmt;
 use std::sync::{atomic, Arc};
+use std::{ffi, fmt};
 
 use crate::gfx;
 use crate::gfx::{Image, Rgba8};
-use crate::math::{Size, Transform};
-use crate::platform::{self, LogicalSize};
+use crate::math::{Rect, Size, Transform};
+use crate::platform::LogicalSize;
 
 /// Identifies a texture in memory.
 #[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
@@ -71,7 +71,13 @@ pub enum Paint {
 #[derive(Debug)]
 pub enum Effect {
     /// Paint.
-    Paint { paint: Paint, blending: Blending },
+    Paint {
+        paint: Paint,
+        blending: Blending,
+        /// Clipping rectangle, in the coordinates of the paint target. Nothing is
+        /// painted outside of it.
+        clip: Option<Rect<f32>>,
+    },
     /// Clear a texture.
     Clear { id: TextureId, color: Rgba8 },
     /// Load a texture.
@@ -91,6 +97,7 @@ impl From<Paint> for Effect {
         Self::Paint {
             paint,
             blending: Blending::default(),
+            clip: None,
         }
     }
 }
@@ -106,16 +113,99 @@ impl TextureStore for HashMap<TextureId, Image> {
     }
 }
 
+/// Loads graphics API functions by name, eg. `glfwGetProcAddress`.
+pub type Loader<'a> = &'a mut dyn FnMut(&str) -> *const ffi::c_void;
+
+/// Where a renderer presents its frames.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Present {
+    /// Present to the default framebuffer of the current context, eg. a window's
+    /// back buffer, or a context owned by a host application.
+    #[default]
+    BackBuffer,
+    /// Don't present. Frames are only rendered to the renderer's own framebuffer.
+    Offscreen,
+}
+
+/// Describes the surface a renderer is created for.
+///
+/// ```
+/// use rgx::gfx::{Present, Surface};
+/// use rgx::platform::LogicalSize;
+///
+/// let surface = Surface::new(LogicalSize::new(640., 480.))
+///     .ui_scale(2.)
+///     .present(Present::Offscreen);
+///
+/// assert!(surface.loader.is_none());
+/// ```
+pub struct Surface<'a> {
+    /// Function loader, for renderers that use a graphics API.
+    pub loader: Option<Loader<'a>>,
+    /// Surface size in logical pixels.
+    pub size: LogicalSize,
+    /// Surface/device scale.
+    pub scale: f64,
+    /// UI scale (user-defined).
+    pub ui_scale: f32,
+    /// Where frames are presented.
+    pub present: Present,
+}
+
+impl<'a> Surface<'a> {
+    /// Create a new surface description of the given size.
+    pub fn new(size: LogicalSize) -> Self {
+        Self {
+            loader: None,
+            size,
+            scale: 1.,
+            ui_scale: 1.,
+            present: Present::default(),
+        }
+    }
+
+    /// Set the function loader.
+    pub fn loader(mut self, loader: Loader<'a>) -> Self {
+        self.loader = Some(loader);
+        self
+    }
+
+    /// Set the surface/device scale.
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Set the UI scale.
+    pub fn ui_scale(mut self, ui_scale: f32) -> Self {
+        self.ui_scale = ui_scale;
+        self
+    }
+
+    /// Set where frames are presented.
+    pub fn present(mut self, present: Present) -> Self {
+        self.present = present;
+        self
+    }
+}
+
+impl<'a> fmt::Debug for Surface<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Surface")
+            .field("loader", &self.loader.is_some())
+            .field("size", &self.size)
+            .field("scale", &self.scale)
+            .field("ui_scale", &self.ui_scale)
+            .field("present", &self.present)
+            .finish()
+    }
+}
+
 /// Renderer trait for all render surfaces.
 pub trait Renderer: Sized {
     type Error;
 
-    fn new(
-        win: &mut platform::backend::Window,
-        win_size: LogicalSize,
-        win_scale: f64,
-        ui_scale: f32,
-    ) -> Result<Self, Self::Error>;
+    fn new(surface: Surface<'_>) -> Result<Self, Self::Error>;
 
     fn frame<E, T>(&mut self, effects: E, store: &mut T) -> Result<(), Self::Error>
     where
@@ -124,4 +214,5 @@ pub trait Renderer: Sized {
 
     fn scale(&mut self, factor: f32) -> f32;
     fn handle_scale_factor_changed(&mut self, scale_factor: f64);
+    fn handle_resized(&mut self, size: LogicalSize);
 }
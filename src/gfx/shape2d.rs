@@ -129,6 +129,7 @@ pub struct Rectangle {
     rotation: Rotation,
     stroke: Stroke,
     fill: Fill,
+    radius: f32,
 }
 
 impl From<Rect<f32>> for Rectangle {
@@ -145,9 +146,13 @@ impl Rectangle {
             rotation: Rotation::default(),
             stroke: Stroke::default(),
             fill: Fill::default(),
+            radius: 0.,
         }
     }
 
+    /// Number of segments used to draw each rounded corner.
+    const CORNER_SEGMENTS: usize = 8;
+
     pub fn zdepth(self, zdepth: impl Into<ZDepth>) -> Self {
         Self {
             zdepth: zdepth.into(),
@@ -168,12 +173,104 @@ impl Rectangle {
         Self { stroke, ..self }
     }
 
+    /// Set the stroke, eg. to draw it inside the rectangle with [`Stroke::inside`].
+    pub fn with_stroke(self, stroke: Stroke) -> Self {
+        Self { stroke, ..self }
+    }
+
     pub fn fill(self, fill: impl Into<Fill>) -> Self {
         Self {
             fill: fill.into(),
             ..self
         }
     }
+
+    /// Round the corners with the given radius.
+    pub fn radius(self, radius: f32) -> Self {
+        Self { radius, ..self }
+    }
+
+    /// Outline of a rounded box, counter-clockwise from the right edge.
+    /// The radius is clamped so that corners don't overlap.
+    fn outline(b: &Box2D<f32>, radius: f32) -> Vec<Point> {
+        let radius = radius
+            .min((b.max.x - b.min.x) / 2.)
+            .min((b.max.y - b.min.y) / 2.)
+            .max(0.);
+        let corners = [
+            Point::new(b.max.x - radius, b.max.y - radius),
+            Point::new(b.min.x + radius, b.max.y - radius),
+            Point::new(b.min.x + radius, b.min.y + radius),
+            Point::new(b.max.x - radius, b.min.y + radius),
+        ];
+        let n = Self::CORNER_SEGMENTS;
+        let mut points = Vec::with_capacity(corners.len() * (n + 1));
+
+        for (i, c) in corners.iter().enumerate() {
+            for j in 0..=n {
+                let angle = (i as f32 + j as f32 / n as f32) * f32::consts::FRAC_PI_2;
+                points.push(Point::new(
+                    c.x + radius * angle.cos(),
+                    c.y + radius * angle.sin(),
+                ));
+            }
+        }
+        points
+    }
+
+    fn rounded_vertices(&self) -> Vec<Vertex> {
+        let ZDepth(z) = self.zdepth;
+        let Rotation { angle, center } = self.rotation;
+        let stroke = &self.stroke;
+        let width = stroke.width;
+        let outer = Box2D::from(self.rect.expand(stroke.offset, stroke.offset));
+        let inner = Box2D::new(
+            Point::new(outer.min.x + width, outer.min.y + width),
+            Point::new(outer.max.x - width, outer.max.y - width),
+        );
+        let radius = self.radius + stroke.offset;
+        let inner_points = Self::outline(&inner, radius - width);
+        let n = inner_points.len();
+        let mut verts = Vec::new();
+
+        if stroke != &Stroke::NONE {
+            let outer_points = Self::outline(&outer, radius);
+            let rgba8 = stroke.color.into();
+
+            for i in 0..n {
+                let (i0, i1) = (inner_points[i], inner_points[(i + 1) % n]);
+                let (o0, o1) = (outer_points[i], outer_points[(i + 1) % n]);
+
+                verts.extend([
+                    vertex(i0.x, i0.y, z, angle, center, rgba8),
+                    vertex(o0.x, o0.y, z, angle, center, rgba8),
+                    vertex(o1.x, o1.y, z, angle, center, rgba8),
+                    vertex(i0.x, i0.y, z, angle, center, rgba8),
+                    vertex(o1.x, o1.y, z, angle, center, rgba8),
+                    vertex(i1.x, i1.y, z, angle, center, rgba8),
+                ]);
+            }
+        }
+
+        if let Fill::Solid(color) = self.fill {
+            let rgba8 = color.into();
+            let mid = Point::new(
+                (inner.min.x + inner.max.x) / 2.,
+                (inner.min.y + inner.max.y) / 2.,
+            );
+
+            for i in 0..n {
+                let (p0, p1) = (inner_points[i], inner_points[(i + 1) % n]);
+
+                verts.extend([
+                    vertex(mid.x, mid.y, z, angle, center, rgba8),
+                    vertex(p0.x, p0.y, z, angle, center, rgba8),
+                    vertex(p1.x, p1.y, z, angle, center, rgba8),
+                ]);
+            }
+        }
+        verts
+    }
 }
 
 pub trait Shape: fmt::Debug {
@@ -182,6 +279,9 @@ pub trait Shape: fmt::Debug {
 
 impl Shape for Rectangle {
     fn vertices(&self) -> Vec<Vertex> {
+        if self.radius > 0. {
+            return self.rounded_vertices();
+        }
         let ZDepth(z) = self.zdepth;
         let Rotation { angle, center } = self.rotation;
         let stroke = &self.stroke;
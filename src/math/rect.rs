@@ -316,12 +316,15 @@ impl<T: Copy> Rect<T> {
     /// ```
     pub fn intersection(&self, other: Rect<T>) -> Option<Self>
     where
-        T: Ord + Add<Output = T> + Sub<Output = T>,
+        T: PartialOrd + Add<Output = T> + Sub<Output = T>,
     {
-        let x1 = T::max(self.origin.x, other.origin.x);
-        let y1 = T::max(self.origin.y, other.origin.y);
-        let x2 = T::min(self.max().x, other.max().x);
-        let y2 = T::min(self.max().y, other.max().y);
+        let max = |a: T, b: T| if a > b { a } else { b };
+        let min = |a: T, b: T| if a < b { a } else { b };
+
+        let x1 = max(self.origin.x, other.origin.x);
+        let y1 = max(self.origin.y, other.origin.y);
+        let x2 = min(self.max().x, other.max().x);
+        let y2 = min(self.max().y, other.max().y);
 
         if x2 < x1 || y2 < y1 {
             None
@@ -8,7 +8,7 @@ pub mod backend;
 #[path = "platform/glfw.rs"]
 pub mod backend;
 
-pub use backend::Cursor;
+pub use backend::{Cursor, Waker};
 
 /// Initialize the platform.
 pub fn init(
@@ -15,6 +15,14 @@ impl Cursor {
     }
 }
 
+/// Wakes up a sleeping event loop.
+#[derive(Debug, Clone)]
+pub struct Waker(());
+
+impl Waker {
+    pub fn wake(&self) {}
+}
+
 pub struct Events {
     #[allow(dead_code)]
     handle: (),
@@ -27,6 +35,10 @@ impl Events {
 
     pub fn poll(&mut self) {}
 
+    pub fn waker(&self) -> Waker {
+        Waker(())
+    }
+
     pub fn flush(&self) -> impl Iterator<Item = WindowEvent> {
         std::iter::empty::<WindowEvent>()
     }
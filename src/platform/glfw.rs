@@ -89,6 +89,16 @@ impl Cursor {
     }
 }
 
+/// Wakes up a sleeping event loop. Can be used from any thread.
+#[derive(Debug, Clone)]
+pub struct Waker(());
+
+impl Waker {
+    pub fn wake(&self) {
+        glfw::Glfw::post_empty_event();
+    }
+}
+
 pub struct Events {
     handle: sync::mpsc::Receiver<(f64, glfw::WindowEvent)>,
     glfw: glfw::Glfw,
@@ -107,6 +117,10 @@ impl Events {
         self.glfw.poll_events();
     }
 
+    pub fn waker(&self) -> Waker {
+        Waker(())
+    }
+
     pub fn flush(&self) -> impl Iterator<Item = WindowEvent> + '_ {
         glfw::flush_messages(&self.handle).map(|(_, e)| e.into())
     }
@@ -1,6 +1,13 @@
 pub mod canvas;
+pub mod command;
+pub mod constraints;
 pub mod context;
 pub mod env;
+pub mod lens;
+pub mod runner;
+pub mod sink;
+pub mod snapshot;
+pub mod testing;
 #[cfg(test)]
 pub mod tests;
 pub mod text;
@@ -17,18 +24,24 @@ use crate::gfx::{Paint, TextureId};
 use crate::math::*;
 
 pub use canvas::*;
+pub use command::{Command, Selector, Target};
+pub use constraints::BoxConstraints;
 pub use context::*;
 pub use env::Env;
+pub use lens::Lens;
+pub use runner::Ui;
+pub use sink::ExtEventSink;
 pub use widgets::align::Align;
 pub use widgets::align::{align, bottom, center, left, right, top};
-pub use widgets::click::Click;
+pub use widgets::click::{Click, ClickEvent, Clicks, LongPress};
 pub use widgets::controller::Control;
 pub use widgets::hover::Hover;
 pub use widgets::hstack::hstack;
 pub use widgets::painter::painter;
+pub use widgets::vstack::vstack;
 pub use widgets::zstack::zstack;
 pub use widgets::Pod;
-pub use widgets::{Widget, WidgetEvent, WidgetExt, WidgetId, WidgetTuple};
+pub use widgets::{Drag, MouseEvent, Widget, WidgetEvent, WidgetExt, WidgetId, WidgetTuple};
 
 /// Off-screen render surfaces.
 pub type Surfaces = HashMap<TextureId, Image>;
@@ -46,8 +59,14 @@ pub struct Interactive<T> {
 }
 
 impl<T> Widget<T> for Interactive<T> {
-    fn layout(&mut self, parent: Size, ctx: &LayoutCtx<'_>, data: &T, env: &Env) -> Size {
-        self.widget.layout(parent, ctx, data, env)
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        self.widget.layout(constraints, ctx, data, env)
     }
 
     fn paint(&mut self, canvas: Canvas<'_>, data: &T) {
@@ -58,6 +77,10 @@ impl<T> Widget<T> for Interactive<T> {
         self.widget.update(ctx, data);
     }
 
+    fn capture(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        self.widget.capture(event, ctx, data)
+    }
+
     fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
         self.widget.event(event, ctx, data)
     }
@@ -66,6 +89,10 @@ impl<T> Widget<T> for Interactive<T> {
         self.widget.contains(point)
     }
 
+    fn accepts_focus(&self) -> bool {
+        self.widget.accepts_focus()
+    }
+
     fn cursor(&self) -> Option<&'static str> {
         self.cursor
     }
@@ -81,6 +108,34 @@ pub trait Interact<T>: Sized + Widget<T> + 'static {
         Control::new(self, Click::new(action))
     }
 
+    /// Invoke the action on every click, with the number of consecutive clicks.
+    fn on_clicks(
+        self,
+        action: impl Fn(&ClickEvent, &Context<'_>, &mut T) + 'static,
+    ) -> Control<Self, Clicks<T>> {
+        Control::new(self, Clicks::new(action))
+    }
+
+    /// Invoke the action when the widget is double-clicked with the left button.
+    fn on_double_click(
+        self,
+        action: impl Fn(&Context<'_>, &mut T) + 'static,
+    ) -> Control<Self, Clicks<T>> {
+        self.on_clicks(move |click, ctx, data| {
+            if click.button == crate::platform::MouseButton::Left && click.count == 2 {
+                action(ctx, data);
+            }
+        })
+    }
+
+    /// Invoke the action when the left button is held down over the widget.
+    fn on_long_press(
+        self,
+        action: impl Fn(&Context<'_>, &mut T) + 'static,
+    ) -> Control<Self, LongPress<T>> {
+        Control::new(self, LongPress::new(action))
+    }
+
     fn on_hover(
         self,
         action: impl Fn(bool, &Context<'_>, &mut T) + 'static,
@@ -98,7 +153,7 @@ pub trait Interact<T>: Sized + Widget<T> + 'static {
 
 impl<T, W> Interact<T> for W where W: Widget<T> + 'static {}
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct Id(u64);
 
 impl Id {
@@ -140,6 +195,12 @@ impl Padding {
     }
 }
 
+impl From<f32> for Padding {
+    fn from(padding: f32) -> Self {
+        Self::all(padding)
+    }
+}
+
 impl From<[f32; 4]> for Padding {
     fn from([top, right, bottom, left]: [f32; 4]) -> Self {
         Self {
@@ -15,6 +15,8 @@ pub struct Canvas<'a> {
     pub size: Size<f32>,
     pub target: Option<TextureId>,
     pub blending: Blending,
+    /// Clipping rectangle, in the coordinates of the paint target.
+    pub clip: Option<Rect<f32>>,
 
     context: &'a Context<'a>,
     graphics: &'a mut Graphics,
@@ -40,6 +42,7 @@ impl<'a> Canvas<'a> {
             size,
             target: None,
             blending: Blending::default(),
+            clip: None,
             context,
             graphics,
         }
@@ -59,6 +62,7 @@ impl<'a> Canvas<'a> {
             size: self.size,
             target: self.target,
             blending: self.blending,
+            clip: self.clip,
             context: self.context,
             graphics: self.graphics,
         }
@@ -70,6 +74,7 @@ impl<'a> Canvas<'a> {
             size,
             target: self.target,
             blending: self.blending,
+            clip: self.clip,
             context: self.context,
             graphics: self.graphics,
         }
@@ -81,6 +86,7 @@ impl<'a> Canvas<'a> {
             size: self.size,
             target: self.target,
             blending: self.blending,
+            clip: self.clip,
             context: self.context,
             graphics: self.graphics,
         }
@@ -104,15 +110,47 @@ impl<'a> Canvas<'a> {
                 paint.transform(self.transform)
             },
             self.blending,
+            self.clip,
         );
     }
 
+    /// Clip painting to the given rectangle, in canvas coordinates. Nested clips are
+    /// intersected.
+    pub fn clip(&mut self, rect: impl Into<Rect<f32>>) -> Canvas<'_> {
+        let rect = rect.into();
+        // Paint on a target isn't transformed.
+        let rect = if self.target.is_some() {
+            rect
+        } else {
+            rect.transform(self.transform)
+        };
+        let clip = match self.clip {
+            // Without an intersection, nothing can be painted.
+            Some(clip) => clip
+                .intersection(rect)
+                .unwrap_or(Rect::new(rect.origin, Size::<f32>::ZERO)),
+            None => rect,
+        };
+
+        Canvas {
+            clip: Some(clip),
+            transform: self.transform,
+            size: self.size,
+            target: self.target,
+            blending: self.blending,
+            context: self.context,
+            graphics: self.graphics,
+        }
+    }
+
     pub fn on(&mut self, texture: TextureId) -> Canvas<'_> {
         Canvas {
             target: Some(texture),
             size: self.size,
             transform: self.transform,
             blending: self.blending,
+            // The clip is in screen coordinates.
+            clip: None,
             context: self.context,
             graphics: self.graphics,
         }
@@ -124,6 +162,7 @@ impl<'a> Canvas<'a> {
             size: self.size,
             blending,
             transform: self.transform,
+            clip: self.clip,
             context: self.context,
             graphics: self.graphics,
         }
@@ -167,6 +206,7 @@ impl<'a> Canvas<'a> {
             transform: self.transform,
             target: self.target,
             blending: self.blending,
+            clip: self.clip,
             context: self.context,
             graphics: self.graphics,
         }
@@ -256,8 +296,12 @@ impl Graphics {
         }
     }
 
-    pub fn paint(&mut self, paint: Paint, blending: Blending) {
-        self.effects.push_back(Effect::Paint { paint, blending });
+    pub fn paint(&mut self, paint: Paint, blending: Blending, clip: Option<Rect<f32>>) {
+        self.effects.push_back(Effect::Paint {
+            paint,
+            blending,
+            clip,
+        });
     }
 
     pub fn clear(&mut self, id: TextureId, color: Rgba8) {
@@ -0,0 +1,154 @@
+//! Commands, for sending messages between widgets.
+//!
+//! ```
+//! use rgx::ui::command::{Command, Selector, Target};
+//! use rgx::ui::WidgetId;
+//!
+//! const OPEN: Selector<String> = Selector::new("open");
+//! const CLOSE: Selector = Selector::new("close");
+//!
+//! let cmd = OPEN.with(String::from("image.rgba"));
+//! assert_eq!(cmd.get(OPEN).map(String::as_str), Some("image.rgba"));
+//! assert_eq!(cmd.get(CLOSE), None);
+//! assert_eq!(cmd.target(), Target::Global);
+//!
+//! let id = WidgetId::next();
+//! let cmd = Command::from(CLOSE).to(id);
+//! assert!(cmd.is(CLOSE));
+//! assert_eq!(cmd.target(), Target::Widget(id));
+//! ```
+use std::any::Any;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::ui::WidgetId;
+
+/// Identifies a command, and the type of its payload.
+pub struct Selector<P = ()> {
+    name: &'static str,
+    payload: PhantomData<fn() -> P>,
+}
+
+impl<P> Selector<P> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            payload: PhantomData,
+        }
+    }
+
+    /// Get the selector name.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<P: Any + Send + Sync> Selector<P> {
+    /// Create a global command with this selector and the given payload.
+    pub fn with(self, payload: P) -> Command {
+        Command {
+            selector: self.name,
+            payload: Arc::new(payload),
+            target: Target::Global,
+        }
+    }
+}
+
+impl<P> Clone for Selector<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P> Copy for Selector<P> {}
+
+impl<P> PartialEq for Selector<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl<P> fmt::Debug for Selector<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Selector({})", self.name)
+    }
+}
+
+/// Where a command is delivered.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// Every widget.
+    Global,
+    /// A single widget.
+    Widget(WidgetId),
+    /// A widget and all its descendants.
+    Subtree(WidgetId),
+}
+
+impl From<WidgetId> for Target {
+    fn from(id: WidgetId) -> Self {
+        Self::Widget(id)
+    }
+}
+
+/// A message sent to widgets, consisting of a selector, a payload and a target.
+#[derive(Clone)]
+pub struct Command {
+    selector: &'static str,
+    payload: Arc<dyn Any + Send + Sync>,
+    target: Target,
+}
+
+impl Command {
+    /// Create a new command.
+    pub fn new<P: Any + Send + Sync>(
+        selector: Selector<P>,
+        payload: P,
+        target: impl Into<Target>,
+    ) -> Self {
+        selector.with(payload).to(target)
+    }
+
+    /// Set the command target.
+    pub fn to(self, target: impl Into<Target>) -> Self {
+        Self {
+            target: target.into(),
+            ..self
+        }
+    }
+
+    /// Get the command target.
+    pub fn target(&self) -> Target {
+        self.target
+    }
+
+    /// Check whether the command has the given selector.
+    pub fn is<P>(&self, selector: Selector<P>) -> bool {
+        self.selector == selector.name
+    }
+
+    /// Get the command payload, if the command has the given selector.
+    pub fn get<P: Any>(&self, selector: Selector<P>) -> Option<&P> {
+        if self.is(selector) {
+            self.payload.downcast_ref()
+        } else {
+            None
+        }
+    }
+}
+
+impl From<Selector> for Command {
+    fn from(selector: Selector) -> Self {
+        selector.with(())
+    }
+}
+
+impl fmt::Debug for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Command")
+            .field("selector", &self.selector)
+            .field("target", &self.target)
+            .finish()
+    }
+}
@@ -0,0 +1,142 @@
+//! Layout constraints.
+//!
+//! During layout, each widget receives [`BoxConstraints`] from its parent, and returns
+//! a size that satisfies them.
+//!
+//! ```
+//! use rgx::ui::BoxConstraints;
+//! use rgx::Size;
+//!
+//! let c = BoxConstraints::new([16., 8.], [64., 32.]);
+//!
+//! assert_eq!(c.constrain([8., 48.]), Size::new(16., 32.));
+//! assert_eq!(c.loosen().constrain([8., 4.]), Size::new(8., 4.));
+//! assert_eq!(c.deflate([8., 8.]), BoxConstraints::new([8., 0.], [56., 24.]));
+//! assert!(BoxConstraints::tight([32., 32.]).is_tight());
+//! ```
+use crate::math::*;
+
+/// Minimum and maximum size a widget can have.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoxConstraints {
+    /// Minimum size.
+    pub min: Size,
+    /// Maximum size. Either dimension may be infinite.
+    pub max: Size,
+}
+
+impl Default for BoxConstraints {
+    fn default() -> Self {
+        Self::UNBOUNDED
+    }
+}
+
+impl BoxConstraints {
+    /// Constraints that allow any size.
+    pub const UNBOUNDED: Self = Self {
+        min: Size::new(0., 0.),
+        max: Size::new(f32::INFINITY, f32::INFINITY),
+    };
+
+    /// Create new constraints. The minimum is clamped to the maximum.
+    pub fn new(min: impl Into<Size>, max: impl Into<Size>) -> Self {
+        let (min, max) = (min.into(), max.into());
+
+        Self {
+            min: Size::new(min.w.min(max.w), min.h.min(max.h)),
+            max,
+        }
+    }
+
+    /// Constraints that only allow the given size.
+    pub fn tight(size: impl Into<Size>) -> Self {
+        let size = size.into();
+
+        Self {
+            min: size,
+            max: size,
+        }
+    }
+
+    /// Constraints that allow any size up to the given size.
+    pub fn loose(size: impl Into<Size>) -> Self {
+        Self {
+            min: Size::ZERO,
+            max: size.into(),
+        }
+    }
+
+    /// Remove the minimum size.
+    pub fn loosen(self) -> Self {
+        Self::loose(self.max)
+    }
+
+    /// Whether only one size is allowed.
+    pub fn is_tight(&self) -> bool {
+        self.min == self.max
+    }
+
+    /// Whether the maximum size is finite in both dimensions.
+    pub fn is_bounded(&self) -> bool {
+        self.max.w.is_finite() && self.max.h.is_finite()
+    }
+
+    /// Clamp a size to these constraints. If the minimum is greater than the maximum,
+    /// the maximum wins.
+    pub fn constrain(&self, size: impl Into<Size>) -> Size {
+        let size = size.into();
+
+        Size::new(
+            size.w.max(self.min.w).min(self.max.w),
+            size.h.max(self.min.h).min(self.max.h),
+        )
+    }
+
+    /// The largest size allowed. Infinite dimensions are replaced with the minimum.
+    pub fn biggest(&self) -> Size {
+        Size::new(
+            if self.max.w.is_finite() {
+                self.max.w
+            } else {
+                self.min.w
+            },
+            if self.max.h.is_finite() {
+                self.max.h
+            } else {
+                self.min.h
+            },
+        )
+    }
+
+    /// The smallest size allowed.
+    pub fn smallest(&self) -> Size {
+        self.min
+    }
+
+    /// Shrink the constraints by the given amount, eg. to make room for padding.
+    pub fn deflate(self, amount: impl Into<Size>) -> Self {
+        let amount = amount.into();
+        let shrink = |n: f32, by: f32| (n - by).max(0.);
+
+        Self {
+            min: Size::new(shrink(self.min.w, amount.w), shrink(self.min.h, amount.h)),
+            max: Size::new(shrink(self.max.w, amount.w), shrink(self.max.h, amount.h)),
+        }
+    }
+
+    /// Set the maximum width. The minimum width is clamped to it.
+    pub fn max_width(self, width: f32) -> Self {
+        Self::new(self.min, Size::new(width, self.max.h))
+    }
+
+    /// Set the maximum height. The minimum height is clamped to it.
+    pub fn max_height(self, height: f32) -> Self {
+        Self::new(self.min, Size::new(self.max.w, height))
+    }
+}
+
+impl From<Size> for BoxConstraints {
+    fn from(size: Size) -> Self {
+        Self::tight(size)
+    }
+}
@@ -1,14 +1,208 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::time;
+
 use super::*;
 
 /// Widget layout context.
 #[derive(Debug, Copy, Clone)]
 pub struct LayoutCtx<'a> {
     pub fonts: &'a HashMap<text::FontId, text::Font>,
+    /// Loaded textures. Used to initialize widgets created after startup.
+    pub textures: &'a HashMap<TextureId, TextureInfo>,
+    /// Where invalidation requests are recorded.
+    requests: Option<&'a RefCell<Requests>>,
 }
 
 impl<'a> LayoutCtx<'a> {
-    pub fn new(fonts: &'a HashMap<text::FontId, text::Font>) -> Self {
-        Self { fonts }
+    pub fn new(
+        fonts: &'a HashMap<text::FontId, text::Font>,
+        textures: &'a HashMap<TextureId, TextureInfo>,
+    ) -> Self {
+        Self {
+            fonts,
+            textures,
+            requests: None,
+        }
+    }
+
+    /// Record invalidation requests made with this context in the given cell.
+    pub fn requests(self, requests: &'a RefCell<Requests>) -> Self {
+        Self {
+            requests: Some(requests),
+            ..self
+        }
+    }
+
+    /// Request another update and layout pass.
+    pub fn request_layout(&self) {
+        if let Some(requests) = self.requests {
+            requests.borrow_mut().layout = true;
+        }
+    }
+}
+
+/// Identifies a timer requested by a widget.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimerToken(u64);
+
+impl TimerToken {
+    pub fn next() -> Self {
+        static NEXT: atomic::AtomicU64 = atomic::AtomicU64::new(1);
+
+        Self(NEXT.fetch_add(1, atomic::Ordering::SeqCst))
+    }
+}
+
+impl fmt::Display for TimerToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TimerToken#{}", self.0)
+    }
+}
+
+/// Identifies an overlay shown by a widget.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OverlayId(u64);
+
+impl OverlayId {
+    pub fn next() -> Self {
+        static NEXT: atomic::AtomicU64 = atomic::AtomicU64::new(1);
+
+        Self(NEXT.fetch_add(1, atomic::Ordering::SeqCst))
+    }
+}
+
+impl fmt::Display for OverlayId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "OverlayId#{}", self.0)
+    }
+}
+
+/// A change to the overlay layer, handled by the nearest
+/// [`crate::ui::widgets::Overlay`] ancestor.
+#[derive(Debug)]
+pub(crate) enum OverlayRequest {
+    /// Show a widget above the rest of the tree.
+    Show {
+        id: OverlayId,
+        /// Position of the widget, in window coordinates.
+        anchor: Point,
+        /// The widget, as a `Box<dyn Widget<T>>`, with `T` the data of the widget that
+        /// requested it, until converted by its ancestors. See [`Requests::lift_overlays`].
+        widget: Box<dyn Any>,
+        /// Name of `T`, to report widgets of the wrong data.
+        data: &'static str,
+        /// Whether input to the widgets below is blocked.
+        modal: bool,
+    },
+    /// Hide a widget shown previously.
+    Hide(OverlayId),
+}
+
+/// Event propagation phase.
+///
+/// Pointer and keyboard events travel down the widget tree to their target, which is
+/// the innermost hot widget for pointer events, or the focused widget for keyboard
+/// events. On the way down, each widget's [`Widget::capture`] method is called. The
+/// event is then delivered with [`Widget::event`] to the target, and bubbles back up
+/// through its ancestors. Returning [`ControlFlow::Break`] stops propagation.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Phase {
+    /// The event is on its way down to the target.
+    Capture,
+    /// The event is delivered to its target.
+    #[default]
+    Target,
+    /// The event is bubbling up from the target.
+    Bubble,
+}
+
+/// Invalidation requests made by widgets. Requests are aggregated up the
+/// widget tree by [`Pod`], and honoured by the UI runner.
+#[derive(Debug, Default)]
+pub struct Requests {
+    /// Whether a repaint was requested.
+    pub paint: bool,
+    /// Whether a re-layout was requested.
+    pub layout: bool,
+    /// Timers requested, with their delay.
+    pub timers: Vec<(TimerToken, time::Duration)>,
+    /// Commands submitted.
+    pub commands: Vec<Command>,
+    /// Widget that requested keyboard focus.
+    pub focus: Option<WidgetId>,
+    /// Focusable widgets, in tree order. Collected during the update pass, when
+    /// the context asks for it, eg. to move focus on `Tab`.
+    pub focus_chain: Vec<WidgetId>,
+    /// Whether the focused widget was updated, and still accepts focus. Focus is
+    /// lost otherwise.
+    pub(crate) focused: bool,
+    /// Whether a widget is hot. Used to find the target of pointer events.
+    pub(crate) hot: bool,
+    /// Widgets added to the tree, on their first update. Used to route events to the
+    /// subtrees containing their target.
+    pub(crate) added: Vec<WidgetId>,
+    /// Widgets removed from the tree, with [`Pod::remove`].
+    pub(crate) removed: Vec<WidgetId>,
+    /// Overlays shown or hidden.
+    pub(crate) overlays: Vec<OverlayRequest>,
+}
+
+impl Requests {
+    /// Merge other requests into these.
+    pub fn merge(&mut self, other: Requests) {
+        self.paint |= other.paint;
+        self.layout |= other.layout;
+        self.timers.extend(other.timers);
+        self.commands.extend(other.commands);
+        self.focus_chain.extend(other.focus_chain);
+        self.added.extend(other.added);
+        self.removed.extend(other.removed);
+        self.overlays.extend(other.overlays);
+        self.hot |= other.hot;
+        self.focused |= other.focused;
+
+        // Requests are merged from the inside out, so the innermost widget wins.
+        if other.focus.is_some() {
+            self.focus = other.focus;
+        }
+    }
+
+    /// Convert the widgets shown in the overlay layer from widgets of `U` to widgets
+    /// of `T`. Used by widgets that give their children part of their data, so that
+    /// the overlay layer receives widgets of its own data. Widgets of other data are
+    /// left as they are.
+    pub(crate) fn lift_overlays<T: 'static, U: 'static>(
+        &mut self,
+        lift: impl Fn(Box<dyn Widget<U>>) -> Box<dyn Widget<T>>,
+    ) {
+        for request in &mut self.overlays {
+            if let OverlayRequest::Show { widget, data, .. } = request {
+                if widget.is::<Box<dyn Widget<U>>>() {
+                    let inner = std::mem::replace(widget, Box::new(()));
+
+                    if let Ok(inner) = inner.downcast::<Box<dyn Widget<U>>>() {
+                        *widget = Box::new(lift(*inner));
+                        *data = std::any::type_name::<T>();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether there are no requests.
+    pub fn is_empty(&self) -> bool {
+        !self.paint
+            && !self.layout
+            && self.timers.is_empty()
+            && self.commands.is_empty()
+            && self.focus.is_none()
+            && self.focus_chain.is_empty()
+            && self.overlays.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && !self.hot
+            && !self.focused
     }
 }
 
@@ -25,6 +219,16 @@ pub struct Context<'a> {
     pub hot: bool,
     /// Whether this widget is active.
     pub active: bool,
+    /// Propagation phase of the current event.
+    pub phase: Phase,
+    /// Id of the widget.
+    widget_id: WidgetId,
+    /// Id of the widget with keyboard focus, if any.
+    focus: Option<WidgetId>,
+    /// Whether focusable widgets add themselves to the focus chain when updated.
+    pub(crate) focus_chain: bool,
+    /// Where invalidation requests are recorded.
+    pub(crate) requests: Option<&'a RefCell<Requests>>,
 }
 
 impl<'a> Context<'a> {
@@ -35,9 +239,172 @@ impl<'a> Context<'a> {
             surfaces,
             hot: false,
             active: false,
+            phase: Phase::Target,
+            widget_id: WidgetId::root(),
+            focus: None,
+            focus_chain: false,
+            requests: None,
+        }
+    }
+
+    /// Record invalidation requests made with this context in the given cell.
+    pub fn requests<'b>(self, requests: &'b RefCell<Requests>) -> Context<'b>
+    where
+        'a: 'b,
+    {
+        Context {
+            requests: Some(requests),
+            ..self
         }
     }
 
+    /// Pass on requests made by children of `U` to the parent, converting the widgets
+    /// they show in the overlay layer to widgets of `T`. See [`Requests::lift_overlays`].
+    pub(crate) fn lift_requests<T: 'static, U: 'static>(
+        &self,
+        requests: RefCell<Requests>,
+        lift: impl Fn(Box<dyn Widget<U>>) -> Box<dyn Widget<T>>,
+    ) {
+        let mut requests = requests.into_inner();
+
+        requests.lift_overlays(lift);
+
+        if let Some(parent) = self.requests {
+            parent.borrow_mut().merge(requests);
+        }
+    }
+
+    /// Set the widget id.
+    pub fn widget_id(self, widget_id: WidgetId) -> Self {
+        Self { widget_id, ..self }
+    }
+
+    /// Get the id of the widget, ie. of its nearest [`Pod`].
+    pub fn id(&self) -> WidgetId {
+        self.widget_id
+    }
+
+    /// Set the widget with keyboard focus.
+    pub fn focus(self, focus: Option<WidgetId>) -> Self {
+        Self { focus, ..self }
+    }
+
+    /// Get the widget with keyboard focus, if any.
+    pub fn focused(&self) -> Option<WidgetId> {
+        self.focus
+    }
+
+    /// Collect the focus chain during the update pass, see [`Requests::focus_chain`].
+    pub(crate) fn focus_chain(self, focus_chain: bool) -> Self {
+        Self {
+            focus_chain,
+            ..self
+        }
+    }
+
+    /// Check whether this widget has keyboard focus.
+    pub fn has_focus(&self) -> bool {
+        self.focus == Some(self.widget_id)
+    }
+
+    /// Request keyboard focus for this widget. The widget should be focusable,
+    /// see [`Widget::accepts_focus`].
+    pub fn request_focus(&self) {
+        if let Some(requests) = self.requests {
+            requests.borrow_mut().focus = Some(self.widget_id);
+        }
+    }
+
+    /// Submit a command. Commands are delivered as [`WidgetEvent::Command`]
+    /// on the next update.
+    pub fn submit_command(&self, command: impl Into<Command>) {
+        if let Some(requests) = self.requests {
+            requests.borrow_mut().commands.push(command.into());
+        }
+    }
+
+    /// Request a repaint. This ensures another frame is drawn, even when redrawing
+    /// on demand, which is useful for animations.
+    pub fn request_paint(&self) {
+        if let Some(requests) = self.requests {
+            requests.borrow_mut().paint = true;
+        }
+    }
+
+    /// Request a re-layout.
+    pub fn request_layout(&self) {
+        if let Some(requests) = self.requests {
+            requests.borrow_mut().layout = true;
+        }
+    }
+
+    /// Request a timer. After the given delay, a [`WidgetEvent::Timer`] with the returned
+    /// token is delivered to the requesting widget.
+    pub fn request_timer(&self, delay: time::Duration) -> TimerToken {
+        let token = TimerToken::next();
+
+        if let Some(requests) = self.requests {
+            requests.borrow_mut().timers.push((token, delay));
+        }
+        token
+    }
+
+    /// Show a widget in the overlay layer, above all other widgets, at the given
+    /// position relative to this widget. The widget receives events before the
+    /// widgets below it, and isn't clipped by its ancestors.
+    ///
+    /// The widget receives the same data as the widget showing it, ie. `T` must be the
+    /// data type of the calling widget, even if it's part of a list or behind a lens.
+    /// Widgets of other data can't be shown: they're reported as errors and dropped,
+    /// and panic in debug builds.
+    pub fn show_overlay<T: 'static>(
+        &self,
+        widget: impl Widget<T> + 'static,
+        position: impl Into<Point>,
+    ) -> OverlayId {
+        self.overlay(Box::new(widget), position.into(), false)
+    }
+
+    /// Show a modal widget in the overlay layer. Like [`Context::show_overlay`], except
+    /// that pointer and keyboard input to the widgets below it is blocked.
+    pub fn show_modal<T: 'static>(
+        &self,
+        widget: impl Widget<T> + 'static,
+        position: impl Into<Point>,
+    ) -> OverlayId {
+        self.overlay(Box::new(widget), position.into(), true)
+    }
+
+    /// Hide a widget shown in the overlay layer.
+    pub fn hide_overlay(&self, id: OverlayId) {
+        if let Some(requests) = self.requests {
+            requests
+                .borrow_mut()
+                .overlays
+                .push(OverlayRequest::Hide(id));
+        }
+    }
+
+    fn overlay<T: 'static>(
+        &self,
+        widget: Box<dyn Widget<T>>,
+        position: Point,
+        modal: bool,
+    ) -> OverlayId {
+        let id = OverlayId::next();
+
+        if let Some(requests) = self.requests {
+            requests.borrow_mut().overlays.push(OverlayRequest::Show {
+                id,
+                anchor: position.transform(self.transform),
+                widget: Box::new(widget),
+                data: std::any::type_name::<T>(),
+                modal,
+            });
+        }
+        id
+    }
+
     /// Offset this context.
     pub fn offset(self, offset: Offset) -> Self {
         self.transform(Transform::translate(offset))
@@ -53,6 +420,11 @@ impl<'a> Context<'a> {
         Self { active, ..self }
     }
 
+    /// Set event propagation phase.
+    pub fn phase(self, phase: Phase) -> Self {
+        Self { phase, ..self }
+    }
+
     /// Transform context.
     pub fn transform(self, t: impl Into<Transform>) -> Self {
         let t = t.into();
@@ -0,0 +1,80 @@
+//! Lenses, for scoping widgets to part of the data.
+//!
+//! A lens gives access to a `U` inside a `T`. With [`crate::ui::WidgetExt::lens`],
+//! a widget written against `U` can be used in a tree of widgets of `T`:
+//!
+//! ```
+//! use rgx::gfx::Rgba8;
+//! use rgx::ui::lens::Field;
+//! use rgx::ui::widgets::Flex;
+//! use rgx::ui::{Interact, WidgetExt};
+//!
+//! /// A reusable counter, which knows nothing of the application data.
+//! fn counter() -> impl WidgetExt<u32> {
+//!     Rgba8::WHITE
+//!         .sized([16., 16.])
+//!         .on_click(|_, count: &mut u32| *count += 1)
+//! }
+//!
+//! struct Data {
+//!     apples: u32,
+//!     oranges: u32,
+//! }
+//!
+//! let apples = Field::new(|d: &Data| &d.apples, |d: &mut Data| &mut d.apples);
+//! let oranges = Field::new(|d: &Data| &d.oranges, |d: &mut Data| &mut d.oranges);
+//! let row = Flex::row()
+//!     .child(counter().lens(apples))
+//!     .child(counter().lens(oranges));
+//! ```
+/// Access to a `U` inside a `T`.
+pub trait Lens<T, U> {
+    /// Get the inner data.
+    fn get<'a>(&self, data: &'a T) -> &'a U;
+    /// Get the inner data, mutably.
+    fn get_mut<'a>(&self, data: &'a mut T) -> &'a mut U;
+}
+
+/// A lens built from a pair of accessors, typically to a field.
+pub struct Field<G, M> {
+    get: G,
+    get_mut: M,
+}
+
+impl<G, M> Field<G, M> {
+    pub fn new<T, U>(get: G, get_mut: M) -> Self
+    where
+        G: Fn(&T) -> &U,
+        M: Fn(&mut T) -> &mut U,
+    {
+        Self { get, get_mut }
+    }
+}
+
+impl<T, U, G, M> Lens<T, U> for Field<G, M>
+where
+    G: Fn(&T) -> &U,
+    M: Fn(&mut T) -> &mut U,
+{
+    fn get<'a>(&self, data: &'a T) -> &'a U {
+        (self.get)(data)
+    }
+
+    fn get_mut<'a>(&self, data: &'a mut T) -> &'a mut U {
+        (self.get_mut)(data)
+    }
+}
+
+/// The lens to the data itself.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Identity;
+
+impl<T> Lens<T, T> for Identity {
+    fn get<'a>(&self, data: &'a T) -> &'a T {
+        data
+    }
+
+    fn get_mut<'a>(&self, data: &'a mut T) -> &'a mut T {
+        data
+    }
+}
@@ -0,0 +1,616 @@
+//! UI runner, for driving a widget tree from an event loop.
+//!
+//! [`crate::Application`] is built on this, but it can also be used to embed a UI
+//! in a host application's own event loop:
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use rgx::gfx::backends::software;
+//! use rgx::gfx::{Renderer, Rgba8, Surface};
+//! use rgx::platform::{LogicalPosition, LogicalSize, WindowEvent};
+//! use rgx::ui::{center, Ui, WidgetExt};
+//!
+//! let size = LogicalSize::new(64., 64.);
+//! let mut renderer = software::Renderer::new(Surface::new(size)).unwrap();
+//! let mut ui = Ui::new(center(Rgba8::RED.sized([16., 16.])), ()).size(size).scale(1.);
+//!
+//! // Once per frame..
+//! ui.handle_event(WindowEvent::CursorMoved {
+//!     position: LogicalPosition::new(32., 32.),
+//! });
+//! ui.update(Duration::from_millis(16));
+//! ui.paint();
+//! ui.render(&mut renderer).unwrap();
+//!
+//! assert_eq!(renderer.screen().sample([32, 32].into()), Some(&Rgba8::RED));
+//! ```
+use std::cell::RefCell;
+use std::time;
+
+use crate::gfx::Renderer;
+use crate::math::*;
+use crate::platform::{self, LogicalSize, WindowEvent};
+use crate::timer::FrameTimer;
+use crate::ui::sink::ExtEvent;
+use crate::ui::widgets::{ClickCounter, Overlay};
+use crate::ui::*;
+
+/// Drives a widget tree and its data.
+///
+/// Each frame, window events are passed in with [`Ui::handle_event`], after which
+/// [`Ui::update`], [`Ui::paint`] and [`Ui::render`] are called in that order.
+pub struct Ui<T> {
+    /// Root of the widget tree.
+    root: Pod<T, Box<dyn Widget<T>>>,
+    /// Application data.
+    data: T,
+    /// Widget environment.
+    env: Env,
+    /// Graphics context.
+    graphics: Graphics,
+    /// Off-screen surfaces rendered to.
+    store: Surfaces,
+    /// Widget events to process on the next update.
+    events: Vec<WidgetEvent>,
+    /// Events sent from outside the UI.
+    sink: ExtEventSink<T>,
+    /// Cursor position, in UI units.
+    cursor: Point,
+    /// Window size in logical pixels.
+    size: LogicalSize,
+    /// UI scale.
+    scale: f32,
+    /// Time elapsed, as the sum of update deltas.
+    time: time::Duration,
+    /// Pending timers, with their deadline.
+    timers: Vec<(TimerToken, time::Duration)>,
+    /// Widget with keyboard focus.
+    focus: Option<WidgetId>,
+    /// Pending move of the focus, in reverse if set. See [`Ui::focus_next`].
+    tab: Option<bool>,
+    /// Counts consecutive clicks, for mouse events.
+    clicks: ClickCounter,
+    /// Click count of the last mouse press.
+    click_count: u32,
+
+    render_timer: FrameTimer,
+    update_timer: FrameTimer,
+    paint_timer: FrameTimer,
+
+    // Window state.
+    initialized: bool,
+    dirty: bool,
+    resized: bool,
+    minimized: bool,
+
+    // Pending renderer state changes.
+    resize: Option<LogicalSize>,
+    scale_factor: Option<f64>,
+}
+
+impl<T: 'static> Ui<T> {
+    /// Create a new UI with the given root widget and initial data.
+    /// The root widget is wrapped in an [`Overlay`], for widgets to show popups in.
+    pub fn new(widget: impl Widget<T> + 'static, data: T) -> Self {
+        Self {
+            root: Pod::new(Box::new(Overlay::new(widget))),
+            data,
+            env: Env::default(),
+            graphics: Graphics::default(),
+            store: Surfaces::new(),
+            events: Vec::with_capacity(16),
+            sink: ExtEventSink::default(),
+            cursor: Point::ORIGIN,
+            size: LogicalSize::new(0., 0.),
+            scale: 1.,
+            time: time::Duration::ZERO,
+            timers: Vec::new(),
+            focus: None,
+            tab: None,
+            clicks: ClickCounter::default(),
+            click_count: 1,
+            render_timer: FrameTimer::new(),
+            update_timer: FrameTimer::new(),
+            paint_timer: FrameTimer::new(),
+            initialized: false,
+            dirty: true,
+            resized: false,
+            minimized: false,
+            resize: None,
+            scale_factor: None,
+        }
+    }
+
+    /// Use the given graphics context, holding textures, fonts and cursors.
+    pub fn graphics(mut self, graphics: Graphics) -> Self {
+        self.graphics = graphics;
+        self
+    }
+
+    /// Use the given widget environment.
+    pub fn env(mut self, env: Env) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// Receive external events from the given sink.
+    pub fn event_sink(mut self, sink: ExtEventSink<T>) -> Self {
+        self.sink = sink;
+        self
+    }
+
+    /// Count consecutive clicks with the given counter, eg. to change the
+    /// double-click interval.
+    pub fn clicks(mut self, clicks: ClickCounter) -> Self {
+        self.clicks = clicks;
+        self
+    }
+
+    /// Get a handle for sending events to the UI from other threads.
+    pub fn sink(&self) -> ExtEventSink<T> {
+        self.sink.clone()
+    }
+
+    /// Set the initial window size, in logical pixels.
+    pub fn size(mut self, size: LogicalSize) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set the UI scale.
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Get the application data.
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    /// Get the application data, mutably.
+    pub fn data_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+
+    /// Consume the UI, returning the application data.
+    pub fn into_data(self) -> T {
+        self.data
+    }
+
+    /// Get the graphics context.
+    pub fn graphics_mut(&mut self) -> &mut Graphics {
+        &mut self.graphics
+    }
+
+    /// Get the off-screen surfaces rendered to.
+    pub fn surfaces(&self) -> &Surfaces {
+        &self.store
+    }
+
+    /// Get the cursor requested by the widget under the mouse, if any.
+    pub fn cursor(&self) -> Option<&'static str> {
+        self.root.cursor()
+    }
+
+    /// Get the widget with keyboard focus, if any.
+    pub fn focused(&self) -> Option<WidgetId> {
+        self.focus
+    }
+
+    /// Give keyboard focus to the given widget, or remove focus if `None`.
+    /// The widget should be focusable, see [`Widget::accepts_focus`].
+    pub fn set_focus(&mut self, focus: Option<WidgetId>) {
+        if focus == self.focus {
+            return;
+        }
+        if let Some(id) = self.focus {
+            self.dispatch(&WidgetEvent::Focus(false), Some(id));
+        }
+        self.focus = focus;
+
+        if let Some(id) = self.focus {
+            self.dispatch(&WidgetEvent::Focus(true), Some(id));
+        }
+        self.dirty = true;
+    }
+
+    /// Move focus to the next focusable widget in tree order, or the previous one
+    /// if `reverse` is set, on the next update. Wraps around at either end.
+    pub fn focus_next(&mut self, reverse: bool) {
+        self.tab = Some(reverse);
+        self.dirty = true;
+    }
+
+    /// Whether the window is minimized. Nothing is updated or rendered in that case.
+    pub fn is_minimized(&self) -> bool {
+        self.minimized
+    }
+
+    /// Whether anything happened since the last update that requires a redraw,
+    /// eg. input was received, or a redraw was requested.
+    pub fn needs_redraw(&self) -> bool {
+        self.dirty || !self.sink.is_empty()
+    }
+
+    /// Request a redraw, eg. because the data was changed from outside the UI.
+    pub fn request_redraw(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Get the size of the UI, in UI units.
+    pub fn ui_size(&self) -> Size {
+        Size::from(self.size) / self.scale
+    }
+
+    /// Handle a window event. Widget events are queued and processed on the next update.
+    ///
+    /// Reading the clipboard is left to the host, see [`Ui::paste`].
+    pub fn handle_event(&mut self, event: WindowEvent) {
+        if event.is_input() {
+            trace!("event: {event:?}");
+        }
+        let events = &mut self.events;
+        let len = events.len();
+
+        match event {
+            WindowEvent::Resized(size) => {
+                if size.is_zero() {
+                    // On certain operating systems, the window size will be set to
+                    // zero when the window is minimized. Since a zero-sized framebuffer
+                    // is not valid, we don't render anything in this case.
+                    self.minimized = true;
+                } else {
+                    self.minimized = false;
+                    self.resized = true;
+                    self.resize = Some(size);
+                    self.size = size;
+                    self.dirty = true;
+                }
+            }
+            WindowEvent::CursorEntered { .. } => {
+                // Do nothing, we generate these based on `CursorMoved`.
+            }
+            WindowEvent::CursorLeft { .. } => {
+                // Do nothing, we generate these based on `CursorMoved`.
+            }
+            WindowEvent::Minimized => {
+                self.minimized = true;
+            }
+            WindowEvent::Restored => {
+                self.minimized = false;
+                self.dirty = true;
+            }
+            WindowEvent::Focused(state) => {
+                events.push(WidgetEvent::WindowFocus(state));
+            }
+            WindowEvent::RedrawRequested => {
+                self.dirty = true;
+            }
+            WindowEvent::ScaleFactorChanged(factor) => {
+                self.scale_factor = Some(factor);
+                self.dirty = true;
+            }
+            WindowEvent::CloseRequested => {
+                // Ignore.
+            }
+            WindowEvent::CursorMoved { position } => {
+                let cursor = Point2D::<f64>::new(position.x, position.y) / self.scale as f64;
+
+                self.cursor = Point::from(cursor.map(|n| n.floor()));
+                events.push(WidgetEvent::MouseMove(self.cursor));
+            }
+            WindowEvent::MouseInput {
+                state,
+                button,
+                modifiers,
+            } => {
+                let mouse = MouseEvent::new(button, self.cursor).modifiers(modifiers);
+
+                match state {
+                    platform::InputState::Pressed => {
+                        self.click_count = self.clicks.click(button, self.cursor);
+                        events.push(WidgetEvent::MouseDown(mouse.count(self.click_count)));
+                    }
+                    platform::InputState::Released => {
+                        events.push(WidgetEvent::MouseUp(mouse.count(self.click_count)));
+                    }
+                    _ => {}
+                }
+            }
+            WindowEvent::Scroll { delta, .. } => {
+                events.push(WidgetEvent::MouseScroll(delta));
+            }
+            WindowEvent::KeyboardInput(input) => match input {
+                platform::KeyboardInput {
+                    state,
+                    key: Some(key),
+                    modifiers,
+                } => match state {
+                    platform::InputState::Pressed => {
+                        events.push(WidgetEvent::KeyDown {
+                            key,
+                            modifiers,
+                            repeat: false,
+                        });
+                    }
+                    platform::InputState::Repeated => {
+                        events.push(WidgetEvent::KeyDown {
+                            key,
+                            modifiers,
+                            repeat: true,
+                        });
+                    }
+                    platform::InputState::Released => {
+                        events.push(WidgetEvent::KeyUp { key, modifiers });
+                    }
+                },
+                _ => {
+                    debug!("Ignored keyboard input with unknown key: {:?}", input);
+                }
+            },
+            WindowEvent::ReceivedCharacter(c, mods) => {
+                events.push(WidgetEvent::CharacterReceived(c, mods));
+            }
+            _ => {}
+        };
+        // Any widget event may change what is displayed.
+        if self.events.len() > len {
+            self.dirty = true;
+        }
+    }
+
+    /// Submit a command, to be delivered to its target on the next update.
+    pub fn submit_command(&mut self, command: impl Into<Command>) {
+        self.events.push(WidgetEvent::Route(command.into()));
+        self.dirty = true;
+    }
+
+    /// Paste text from the clipboard, on the next update.
+    pub fn paste(&mut self, text: Option<String>) {
+        self.events.push(WidgetEvent::Paste(text));
+        self.dirty = true;
+    }
+
+    /// Process pending events, then run the update and layout passes.
+    /// The delta is the time elapsed since the last update.
+    pub fn update(&mut self, delta: time::Duration) {
+        if !self.initialized {
+            self.initialize();
+        }
+        if self.minimized {
+            return;
+        }
+        self.dirty = false;
+        self.time += delta;
+        self.clicks.tick(delta);
+
+        let win_size_ui = self.ui_size();
+
+        // Since we may receive multiple resize events at once, instead of responded to each
+        // resize event, we handle the resize only once.
+        if self.resized {
+            self.resized = false;
+            self.events.push(WidgetEvent::Resized(win_size_ui));
+        }
+        self.dispatch(&WidgetEvent::Tick(delta), self.focus);
+
+        // A common case is that we have multiple `CursorMoved` events
+        // in one update. In that case we keep only the last one,
+        // since the in-betweens will never be seen.
+        if self.events.len() > 1
+            && self
+                .events
+                .iter()
+                .all(|e| matches!(e, WidgetEvent::MouseMove(_)))
+        {
+            self.events.drain(..self.events.len() - 1);
+        }
+
+        // Receive external events.
+        for event in self.sink.drain() {
+            match event {
+                ExtEvent::Command(cmd) => {
+                    self.events.push(WidgetEvent::Route(cmd));
+                }
+                ExtEvent::Update(f) => {
+                    f(&mut self.data);
+                }
+            }
+        }
+
+        // Fire expired timers.
+        let time = self.time;
+        self.timers.retain(|(token, deadline)| {
+            if *deadline <= time {
+                self.events.push(WidgetEvent::Timer(*token));
+                false
+            } else {
+                true
+            }
+        });
+
+        for ev in std::mem::take(&mut self.events) {
+            match ev {
+                // `Tab` and `Shift-Tab` cycle through focusable widgets, unless
+                // the focused widget handles them.
+                WidgetEvent::KeyDown {
+                    key: platform::Key::Tab,
+                    modifiers,
+                    ..
+                } => {
+                    if self.dispatch(&ev, self.focus).is_continue() {
+                        self.focus_next(modifiers.shift);
+                    }
+                }
+                _ => {
+                    self.dispatch(&ev, self.focus);
+                }
+            }
+        }
+
+        let requests = RefCell::default();
+        // The focus chain is only collected when focus has to move.
+        let ctx = Context::new(self.cursor, &self.store)
+            .focus(self.focus)
+            .focus_chain(self.tab.is_some())
+            .requests(&requests);
+        let (root, data, env, fonts, textures) = (
+            &mut self.root,
+            &self.data,
+            &self.env,
+            &self.graphics.fonts,
+            &self.graphics.textures,
+        );
+
+        self.update_timer.run(|_avg| {
+            root.update(&ctx, data);
+            root.layout(
+                BoxConstraints::tight(win_size_ui),
+                &LayoutCtx::new(fonts, textures).requests(&requests),
+                data,
+                env,
+            );
+        });
+        self.handle_update_requests(requests.into_inner());
+    }
+
+    /// Send an event to the widget tree, with the given widget focused, and handle
+    /// the resulting requests.
+    fn dispatch(&mut self, event: &WidgetEvent, focus: Option<WidgetId>) -> ControlFlow<()> {
+        let requests = RefCell::default();
+        let ctx = Context::new(self.cursor, &self.store)
+            .focus(focus)
+            .requests(&requests);
+
+        let flow = self.root.event(event, &ctx, &mut self.data);
+        self.handle_requests(requests.into_inner());
+
+        flow
+    }
+
+    /// Get the time until the next timer fires, if any timers are pending.
+    /// When redrawing on demand, this is how long the event loop can sleep for.
+    pub fn timeout(&self) -> Option<time::Duration> {
+        self.timers
+            .iter()
+            .map(|(_, deadline)| deadline.saturating_sub(self.time))
+            .min()
+    }
+
+    /// Handle requests made during the update pass, and move focus if requested
+    /// with [`Ui::focus_next`].
+    fn handle_update_requests(&mut self, mut requests: Requests) {
+        // Focus is lost when the focused widget goes away, or stops accepting focus.
+        if let Some(id) = self.focus {
+            if !requests.focused {
+                self.focus = None;
+                self.dispatch(&WidgetEvent::Focus(false), Some(id));
+            }
+        }
+        if let Some(reverse) = self.tab.take() {
+            let chain = std::mem::take(&mut requests.focus_chain);
+            let len = chain.len();
+            let current = self
+                .focus
+                .and_then(|id| chain.iter().position(|f| *f == id));
+            let next = match (current, reverse) {
+                (Some(i), false) => Some((i + 1) % len),
+                (Some(i), true) => Some((i + len - 1) % len),
+                (None, _) if len == 0 => None,
+                (None, false) => Some(0),
+                (None, true) => Some(len - 1),
+            };
+            if let Some(next) = next {
+                self.set_focus(Some(chain[next]));
+            }
+        }
+        self.handle_requests(requests);
+    }
+
+    fn handle_requests(&mut self, requests: Requests) {
+        // Layout is currently run on every update, so a layout request only
+        // has to ensure that there is another update.
+        if requests.paint || requests.layout {
+            self.dirty = true;
+        }
+        for (token, delay) in requests.timers {
+            self.timers.push((token, self.time + delay));
+        }
+        for cmd in requests.commands {
+            self.submit_command(cmd);
+        }
+        if let Some(id) = requests.focus {
+            self.set_focus(Some(id));
+        }
+    }
+
+    /// Run the paint pass. Paint effects are queued until the next render.
+    pub fn paint(&mut self) {
+        if self.minimized {
+            return;
+        }
+        let win_size_ui = self.ui_size();
+        let ctx = Context::new(self.cursor, &self.store);
+        let (root, data, graphics) = (&mut self.root, &self.data, &mut self.graphics);
+
+        self.paint_timer.run(|_avg| {
+            root.paint(
+                Canvas::new(&ctx, graphics, Transform::identity(), win_size_ui),
+                data,
+            );
+        });
+    }
+
+    /// Render the queued paint effects with the given renderer.
+    pub fn render<R: Renderer>(&mut self, renderer: &mut R) -> Result<(), R::Error> {
+        if let Some(factor) = self.scale_factor.take() {
+            renderer.handle_scale_factor_changed(factor);
+        }
+        if self.minimized {
+            return Ok(());
+        }
+        if let Some(size) = self.resize.take() {
+            renderer.handle_resized(size);
+        }
+        let (root, data, graphics, store) = (
+            &mut self.root,
+            &mut self.data,
+            &mut self.graphics,
+            &mut self.store,
+        );
+
+        self.render_timer.run(|_avg| {
+            renderer.frame(graphics.effects(), store)?;
+            root.frame(store, data);
+
+            Ok(())
+        })
+    }
+
+    fn initialize(&mut self) {
+        let requests = RefCell::default();
+        let ctx = Context::new(Point::ORIGIN, &self.store).requests(&requests);
+
+        self.initialized = true;
+        self.root.lifecycle(
+            &WidgetLifecycle::Initialized(&self.graphics.textures),
+            &ctx,
+            &self.data,
+            &self.env,
+        );
+        // Initial update and layout so that the first events, eg. `CursorMove` work.
+        // If we don't do this, widget sizes will be zero when the first events land.
+        // It's important however that in the general case, update and layout are run
+        // *after* events are processed.
+        self.root.update(&ctx, &self.data);
+        self.root.layout(
+            BoxConstraints::tight(self.ui_size()),
+            &LayoutCtx::new(&self.graphics.fonts, &self.graphics.textures).requests(&requests),
+            &self.data,
+            &self.env,
+        );
+        self.handle_update_requests(requests.into_inner());
+    }
+}
@@ -0,0 +1,132 @@
+//! External event sink, for sending events to the UI from other threads.
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::ui::Command;
+
+/// An event sent from outside the UI.
+pub(crate) enum ExtEvent<T> {
+    /// Deliver a command to widgets.
+    Command(Command),
+    /// Mutate the application data.
+    Update(Box<dyn FnOnce(&mut T) + Send>),
+}
+
+struct Inner<T> {
+    /// Events not yet received by the UI.
+    queue: VecDeque<ExtEvent<T>>,
+    /// Wakes up the event loop, if it's sleeping.
+    waker: Option<Arc<dyn Fn() + Send + Sync>>,
+}
+
+/// A handle for sending events to a UI with data of type `T` from other threads.
+///
+/// Events are received by the UI on its next update. If the event loop is sleeping,
+/// it is woken up.
+///
+/// ```
+/// use std::thread;
+/// use rgx::ui::{ExtEventSink, Selector};
+///
+/// const LOADED: Selector<String> = Selector::new("loaded");
+///
+/// let sink = ExtEventSink::<u32>::default();
+/// let handle = sink.clone();
+///
+/// thread::spawn(move || {
+///     handle.submit_command(LOADED.with(String::from("image.rgba")));
+///     handle.update(|count| *count += 1);
+/// })
+/// .join()
+/// .unwrap();
+///
+/// assert_eq!(sink.len(), 2);
+/// ```
+pub struct ExtEventSink<T> {
+    inner: Arc<Mutex<Inner<T>>>,
+}
+
+impl<T> ExtEventSink<T> {
+    /// Submit a command, which is delivered to its target as a
+    /// [`crate::ui::WidgetEvent::Command`].
+    pub fn submit_command(&self, command: impl Into<Command>) {
+        self.push(ExtEvent::Command(command.into()));
+    }
+
+    /// Mutate the application data.
+    pub fn update(&self, f: impl FnOnce(&mut T) + Send + 'static) {
+        self.push(ExtEvent::Update(Box::new(f)));
+    }
+
+    /// Number of events that haven't been received yet.
+    pub fn len(&self) -> usize {
+        self.lock().queue.len()
+    }
+
+    /// Whether there are no events waiting to be received.
+    pub fn is_empty(&self) -> bool {
+        self.lock().queue.is_empty()
+    }
+
+    /// Set the function used to wake up the event loop.
+    pub fn set_waker(&self, waker: impl Fn() + Send + Sync + 'static) {
+        self.lock().waker = Some(Arc::new(waker));
+    }
+
+    /// Remove the function used to wake up the event loop, eg. once the loop has exited.
+    /// Events are still queued, but nothing is woken up.
+    pub fn clear_waker(&self) {
+        self.lock().waker = None;
+    }
+
+    /// Take all pending events.
+    pub(crate) fn drain(&self) -> VecDeque<ExtEvent<T>> {
+        std::mem::take(&mut self.lock().queue)
+    }
+
+    fn push(&self, event: ExtEvent<T>) {
+        let waker = {
+            let mut inner = self.lock();
+
+            inner.queue.push_back(event);
+            inner.waker.clone()
+        };
+        // Wake the loop outside of the lock, so that it can receive the event.
+        if let Some(wake) = waker {
+            wake();
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner<T>> {
+        // The lock is never held while running user code, so it can't be poisoned.
+        self.inner.lock().unwrap()
+    }
+}
+
+impl<T> Default for ExtEventSink<T> {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                queue: VecDeque::new(),
+                waker: None,
+            })),
+        }
+    }
+}
+
+impl<T> Clone for ExtEventSink<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for ExtEventSink<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtEventSink")
+            .field("pending", &self.len())
+            .finish()
+    }
+}
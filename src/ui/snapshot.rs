@@ -0,0 +1,141 @@
+//! Offline rendering of widget trees to images.
+//!
+//! ```
+//! use rgx::gfx::Rgba8;
+//! use rgx::Size;
+//! use rgx::ui::{center, snapshot, Env, Graphics, WidgetExt};
+//!
+//! let widget = center(Rgba8::RED.sized([16., 16.]));
+//! let mut graphics = Graphics::default();
+//! let image = snapshot::render(widget, &(), &Env::default(), &mut graphics, [32., 32.], 1.)
+//!     .unwrap();
+//!
+//! assert_eq!(image.size, Size::new(32, 32));
+//! assert_eq!(image.sample([16, 16].into()), Some(&Rgba8::RED));
+//! assert_eq!(image.sample([0, 0].into()), Some(&Rgba8::TRANSPARENT));
+//! ```
+use std::collections::HashMap;
+
+use crate::gfx::backends::software;
+use crate::gfx::{Image, TextureId};
+use crate::math::*;
+use crate::platform::LogicalSize;
+use crate::ui::*;
+
+/// Renders widget trees to images, without a window.
+///
+/// Since textures are only sent to the renderer once, a [`Graphics`] context should
+/// always be used with the same [`Snapshot`].
+pub struct Snapshot {
+    /// Size of the snapshot, in UI units.
+    size: Size,
+    /// Scale of the snapshot, ie. the number of pixels per UI unit.
+    scale: f32,
+    renderer: software::Renderer,
+    /// Off-screen surfaces rendered to.
+    store: HashMap<TextureId, Image>,
+}
+
+impl Snapshot {
+    /// Create a new snapshot renderer. The resulting images are `size * scale` pixels large.
+    pub fn new(size: impl Into<Size>, scale: f32) -> Self {
+        let size = size.into();
+
+        Self {
+            size,
+            scale,
+            renderer: software::Renderer::with_size(Self::win_size(size, scale), 1., scale),
+            store: HashMap::new(),
+        }
+    }
+
+    /// Get the off-screen surfaces rendered to so far.
+    pub fn surfaces(&self) -> &Surfaces {
+        &self.store
+    }
+
+    /// Change the size and scale of the snapshots.
+    pub fn resize(&mut self, size: impl Into<Size>, scale: f32) {
+        let size = size.into();
+
+        if size == self.size && scale == self.scale {
+            return;
+        }
+        self.size = size;
+        self.scale = scale;
+        self.renderer.ui_scale = scale;
+        self.renderer.handle_resized(Self::win_size(size, scale));
+    }
+
+    /// Render a widget with the given data. Runs the widget's lifecycle, update, layout
+    /// and paint passes, and returns the rendered image.
+    pub fn render<T>(
+        &mut self,
+        widget: impl Widget<T>,
+        data: &T,
+        env: &Env,
+        graphics: &mut Graphics,
+    ) -> Result<Image, software::Error> {
+        let mut root = Pod::new(widget);
+        let ctx = Context::new(Point::ORIGIN, &self.store);
+
+        root.lifecycle(
+            &WidgetLifecycle::Initialized(&graphics.textures),
+            &ctx,
+            data,
+            env,
+        );
+        root.update(&ctx, data);
+        root.layout(
+            BoxConstraints::tight(self.size),
+            &LayoutCtx::new(&graphics.fonts, &graphics.textures),
+            data,
+            env,
+        );
+        self.paint(&mut root, data, graphics)
+    }
+
+    /// Paint a widget that was already laid out, and return the rendered image.
+    pub fn paint<T>(
+        &mut self,
+        widget: &mut impl Widget<T>,
+        data: &T,
+        graphics: &mut Graphics,
+    ) -> Result<Image, software::Error> {
+        let ctx = Context::new(Point::ORIGIN, &self.store);
+
+        widget.paint(
+            Canvas::new(&ctx, graphics, Transform::identity(), self.size),
+            data,
+        );
+        self.renderer.frame(graphics.effects(), &mut self.store)?;
+
+        Ok(self.renderer.screen())
+    }
+
+    /// Scale of the snapshot.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    fn win_size(size: Size, scale: f32) -> LogicalSize {
+        LogicalSize::new(
+            (size.w * scale).round() as f64,
+            (size.h * scale).round() as f64,
+        )
+    }
+}
+
+/// Render a widget to an image of `size * scale` pixels.
+///
+/// See [`Snapshot::render`].
+pub fn render<T>(
+    widget: impl Widget<T>,
+    data: &T,
+    env: &Env,
+    graphics: &mut Graphics,
+    size: impl Into<Size>,
+    scale: f32,
+) -> Result<Image, software::Error> {
+    Snapshot::new(size, scale).render(widget, data, env, graphics)
+}
@@ -0,0 +1,450 @@
+//! Widget testing harness.
+//!
+//! An [`Environment`] hosts a widget tree outside of an application, and lets tests
+//! drive it with mouse events, and inspect what it paints. Painted output can be
+//! compared against reference images stored as `.rgba` files:
+//!
+//! ```
+//! use rgx::gfx::Rgba8;
+//! use rgx::ui::{center, testing::Environment, WidgetExt};
+//!
+//! let mut e = Environment::new(center(Rgba8::RED.sized([16., 16.])));
+//! e.layout([64., 64.], &());
+//!
+//! let image = e.capture(&()).unwrap();
+//! assert_eq!(image.sample([32, 32].into()), Some(&Rgba8::RED));
+//!
+//! // Reference images are usually created by running the tests with `RGX_BLESS` set.
+//! let path = std::env::temp_dir().join("rgx-doctest-red.rgba");
+//! image.write(std::fs::File::create(&path).unwrap()).unwrap();
+//!
+//! e.snapshot(&(), &path).unwrap();
+//! ```
+//!
+//! When a snapshot doesn't match its reference image, a diff image is written next to
+//! it, with the differing pixels in red. To create or update reference images, run the
+//! tests with the `RGX_BLESS` environment variable set.
+use std::cell::{Ref, RefCell};
+use std::path::{Path, PathBuf};
+use std::{fs, io, time};
+
+use thiserror::Error;
+
+use crate::gfx::backends::software;
+use crate::gfx::color::ImageError;
+use crate::gfx::{Image, Rgba8};
+use crate::math::*;
+use crate::platform::MouseButton;
+use crate::ui::snapshot::Snapshot;
+use crate::ui::widgets::ClickCounter;
+use crate::ui::*;
+
+/// Environment variable which, when set, causes snapshots to overwrite their
+/// reference images instead of being compared with them.
+pub const BLESS_VAR: &str = "RGX_BLESS";
+
+/// Testing error.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("i/o error: {0}")]
+    Io(#[from] io::Error),
+    #[error("render error: {0}")]
+    Render(#[from] software::Error),
+    #[error("reference image {0:?} is invalid: {1}")]
+    Invalid(PathBuf, ImageError),
+    #[error("reference image {0:?} not found, set `{BLESS_VAR}` to create it")]
+    NotFound(PathBuf),
+    #[error("snapshot size {actual:?} differs from reference image {path:?} of size {expected:?}")]
+    SizeMismatch {
+        path: PathBuf,
+        actual: Size<u32>,
+        expected: Size<u32>,
+    },
+    #[error("{pixels} pixel(s) differ from reference image {path:?}, see {diff:?}")]
+    Mismatch {
+        path: PathBuf,
+        diff: PathBuf,
+        pixels: usize,
+    },
+}
+
+/// A test environment for a widget tree.
+///
+/// Requests made by widgets are handled like the UI runner would, after each event:
+/// commands are delivered, keyboard focus is moved, timers are scheduled to fire
+/// on [`Environment::tick`], and the tree is updated and laid out again if requested.
+/// Requests made outside of events are handled after the next event.
+pub struct Environment<T, W> {
+    /// Root of the widget tree.
+    pub root: Pod<T, W>,
+    /// Widget environment.
+    pub env: Env,
+    /// Graphics context, holding textures and fonts.
+    pub graphics: Graphics,
+    /// Cursor position.
+    pub cursor: Point,
+    /// Counts consecutive clicks, for mouse events.
+    clicks: ClickCounter,
+    /// Click count of the last mouse press.
+    click_count: u32,
+    /// Requests made by widgets. Paint and layout requests are kept until the next
+    /// capture and layout, respectively.
+    requests: RefCell<Requests>,
+    /// Widget with keyboard focus.
+    focus: Option<WidgetId>,
+    /// Widget which lost focus when it was no longer updated, or stopped accepting it, and wasn't told yet.
+    blurred: Option<WidgetId>,
+    /// Timers requested by widgets, with their deadline.
+    timers: Vec<(TimerToken, time::Duration)>,
+    /// Time elapsed, as of the last tick.
+    time: time::Duration,
+    /// Size of the environment, as of the last layout.
+    size: Size,
+    /// Pixels per UI unit, when capturing.
+    scale: f32,
+    snapshot: Snapshot,
+    initialized: bool,
+}
+
+impl<T, W: Widget<T>> Environment<T, W> {
+    /// Create a new environment around a widget.
+    pub fn new(widget: W) -> Self {
+        Self {
+            root: Pod::new(widget),
+            env: Env::default(),
+            graphics: Graphics::default(),
+            cursor: Point::ORIGIN,
+            clicks: ClickCounter::default(),
+            click_count: 1,
+            requests: RefCell::default(),
+            focus: None,
+            blurred: None,
+            timers: Vec::new(),
+            time: time::Duration::ZERO,
+            size: Size::ZERO,
+            scale: 1.,
+            snapshot: Snapshot::new(Size::<f32>::ZERO, 1.),
+            initialized: false,
+        }
+    }
+
+    /// Use the given widget environment.
+    pub fn env(mut self, env: Env) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// Use the given graphics context, eg. to make fonts available.
+    pub fn graphics(mut self, graphics: Graphics) -> Self {
+        self.graphics = graphics;
+        self
+    }
+
+    /// Count consecutive clicks with the given counter.
+    pub fn clicks(mut self, clicks: ClickCounter) -> Self {
+        self.clicks = clicks;
+        self
+    }
+
+    /// Set the number of pixels per UI unit used when capturing.
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Get the widget context, as it would be passed to the root widget.
+    pub fn context(&self) -> Context<'_> {
+        Context::new(self.cursor, self.snapshot.surfaces())
+            .focus(self.focus)
+            .requests(&self.requests)
+    }
+
+    /// Get the off-screen surfaces rendered to.
+    pub fn surfaces(&self) -> &Surfaces {
+        self.snapshot.surfaces()
+    }
+
+    /// Get the requests made by widgets that weren't handled yet.
+    pub fn requests(&self) -> Ref<'_, Requests> {
+        self.requests.borrow()
+    }
+
+    /// Get the widget with keyboard focus, if any.
+    pub fn focused(&self) -> Option<WidgetId> {
+        self.focus
+    }
+
+    /// Run the update pass.
+    pub fn update(&mut self, data: &T) -> &mut Self {
+        self.initialize(data);
+
+        let ctx = Context::new(self.cursor, self.snapshot.surfaces())
+            .focus(self.focus)
+            .requests(&self.requests);
+        self.root.update(&ctx, data);
+
+        let mut requests = self.requests.borrow_mut();
+        let focused = std::mem::take(&mut requests.focused);
+
+        // The root has no ancestors to route events through.
+        requests.added.clear();
+        requests.removed.clear();
+
+        // Focus is lost when the focused widget goes away, or stops accepting focus.
+        if let Some(id) = self.focus {
+            if !focused {
+                self.focus = None;
+                self.blurred = Some(id);
+            }
+        }
+        drop(requests);
+
+        self
+    }
+
+    /// Run the layout pass, with the root widget constrained to the given size.
+    pub fn layout(&mut self, size: impl Into<Size>, data: &T) -> &mut Self {
+        self.initialize(data);
+        self.size = size.into();
+        self.layout_pass(data);
+
+        // Widgets initialized during layout pass on their requests with the next update.
+        if self.requests.borrow().layout {
+            self.update(data).layout_pass(data);
+        }
+        self
+    }
+
+    /// Send an event to the widget tree, and handle the resulting requests.
+    pub fn event(&mut self, event: &WidgetEvent, data: &mut T) -> &mut Self {
+        self.dispatch(event, self.focus, data);
+        self.handle_requests(data);
+        self
+    }
+
+    /// Give keyboard focus to the given widget, or remove focus if `None`.
+    pub fn set_focus(&mut self, focus: Option<WidgetId>, data: &mut T) -> &mut Self {
+        if focus != self.focus {
+            if let Some(id) = self.focus {
+                self.dispatch(&WidgetEvent::Focus(false), Some(id), data);
+            }
+            self.focus = focus;
+
+            if let Some(id) = self.focus {
+                self.dispatch(&WidgetEvent::Focus(true), Some(id), data);
+            }
+        }
+        self.handle_requests(data);
+        self
+    }
+
+    /// Advance time, sending a tick and firing the timers that expired.
+    pub fn tick(&mut self, delta: time::Duration, data: &mut T) -> &mut Self {
+        // Schedule the timers requested outside of events, eg. during initialization.
+        self.handle_requests(data);
+
+        self.time += delta;
+        self.clicks.tick(delta);
+        self.dispatch(&WidgetEvent::Tick(delta), self.focus, data);
+
+        let time = self.time;
+        let (expired, pending) = self
+            .timers
+            .drain(..)
+            .partition::<Vec<_>, _>(|(_, deadline)| *deadline <= time);
+
+        self.timers = pending;
+
+        for (token, _) in expired {
+            self.dispatch(&WidgetEvent::Timer(token), self.focus, data);
+        }
+        self.handle_requests(data);
+        self
+    }
+
+    /// Move the cursor to the given point.
+    pub fn hover(&mut self, point: impl Into<Point>, data: &mut T) -> &mut Self {
+        let point = point.into();
+
+        self.cursor = point;
+        self.event(&WidgetEvent::MouseMove(point), data)
+    }
+
+    /// Press the left mouse button at the current cursor position. Consecutive presses
+    /// are counted as multi-clicks, if time doesn't advance too much between them.
+    pub fn press(&mut self, data: &mut T) -> &mut Self {
+        self.click_count = self.clicks.click(MouseButton::Left, self.cursor);
+        let mouse = MouseEvent::new(MouseButton::Left, self.cursor).count(self.click_count);
+
+        self.event(&WidgetEvent::MouseDown(mouse), data)
+    }
+
+    /// Release the left mouse button at the current cursor position.
+    pub fn release(&mut self, data: &mut T) -> &mut Self {
+        let mouse = MouseEvent::new(MouseButton::Left, self.cursor).count(self.click_count);
+
+        self.event(&WidgetEvent::MouseUp(mouse), data)
+    }
+
+    /// Click with the left mouse button at the current cursor position.
+    pub fn click(&mut self, data: &mut T) -> &mut Self {
+        self.press(data).release(data)
+    }
+
+    /// Paint the widget tree and render it to an image. The image is the size of the
+    /// last layout, multiplied by the scale.
+    pub fn capture(&mut self, data: &T) -> Result<Image, Error> {
+        self.snapshot.resize(self.size, self.scale);
+        self.requests.borrow_mut().paint = false;
+
+        Ok(self
+            .snapshot
+            .paint(&mut self.root, data, &mut self.graphics)?)
+    }
+
+    /// Capture the widget tree and compare it with the reference image at the given path.
+    ///
+    /// On mismatch, a diff image is written next to the reference, with a `.diff.rgba`
+    /// extension. If [`BLESS_VAR`] is set, the reference image is written instead.
+    pub fn snapshot(&mut self, data: &T, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        let actual = self.capture(data)?;
+
+        if std::env::var_os(BLESS_VAR).is_some() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            actual.write(fs::File::create(path)?)?;
+
+            return Ok(());
+        }
+        compare(&actual, path)
+    }
+
+    fn layout_pass(&mut self, data: &T) {
+        self.requests.borrow_mut().layout = false;
+        self.root.layout(
+            BoxConstraints::tight(self.size),
+            &LayoutCtx::new(&self.graphics.fonts, &self.graphics.textures).requests(&self.requests),
+            data,
+            &self.env,
+        );
+    }
+
+    /// Send an event to the widget tree, with the given widget focused.
+    fn dispatch(&mut self, event: &WidgetEvent, focus: Option<WidgetId>, data: &mut T) {
+        let ctx = Context::new(self.cursor, self.snapshot.surfaces())
+            .focus(focus)
+            .requests(&self.requests);
+
+        self.root.event(event, &ctx, data);
+    }
+
+    /// Handle the requests made by widgets so far.
+    fn handle_requests(&mut self, data: &mut T) {
+        if let Some(id) = self.blurred.take() {
+            self.dispatch(&WidgetEvent::Focus(false), Some(id), data);
+        }
+        loop {
+            let mut requests = self.requests.borrow_mut();
+            let commands = std::mem::take(&mut requests.commands);
+            let focus = requests.focus.take();
+
+            requests.added.clear();
+            requests.removed.clear();
+
+            for (token, delay) in requests.timers.drain(..) {
+                self.timers.push((token, self.time + delay));
+            }
+            drop(requests);
+
+            if let Some(id) = focus {
+                self.set_focus(Some(id), data);
+            }
+            if commands.is_empty() {
+                break;
+            }
+            for cmd in commands {
+                self.dispatch(&WidgetEvent::Route(cmd), self.focus, data);
+            }
+        }
+        if self.requests.borrow().layout {
+            let size = self.size;
+            self.update(data).layout(size, data);
+        }
+    }
+
+    fn initialize(&mut self, data: &T) {
+        if self.initialized {
+            return;
+        }
+        self.initialized = true;
+        self.root.lifecycle(
+            &WidgetLifecycle::Initialized(&self.graphics.textures),
+            &Context::new(self.cursor, self.snapshot.surfaces()).requests(&self.requests),
+            data,
+            &self.env,
+        );
+        // Initial update, so that the tree is ready to receive events.
+        self.update(data);
+    }
+}
+
+/// Compare an image with the reference image at the given path. Writes a diff image
+/// next to the reference image on mismatch.
+pub fn compare(actual: &Image, path: impl AsRef<Path>) -> Result<(), Error> {
+    let path = path.as_ref();
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Err(Error::NotFound(path.to_path_buf()));
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let expected =
+        Image::try_from(bytes.as_slice()).map_err(|e| Error::Invalid(path.to_path_buf(), e))?;
+
+    if actual.size != expected.size {
+        return Err(Error::SizeMismatch {
+            path: path.to_path_buf(),
+            actual: actual.size,
+            expected: expected.size,
+        });
+    }
+    if actual == &expected {
+        return Ok(());
+    }
+
+    let (diff, pixels) = diff(actual, &expected);
+    let diff_path = path.with_extension("diff.rgba");
+
+    diff.write(fs::File::create(&diff_path)?)?;
+
+    Err(Error::Mismatch {
+        path: path.to_path_buf(),
+        diff: diff_path,
+        pixels,
+    })
+}
+
+/// Compute the difference between two images of the same size. Differing pixels are
+/// red, while others are a faded version of the expected image.
+/// Returns the diff image along with the number of differing pixels.
+fn diff(actual: &Image, expected: &Image) -> (Image, usize) {
+    let mut count = 0;
+    let pixels = actual
+        .pixels
+        .iter()
+        .zip(expected.pixels.iter())
+        .map(|(a, e)| {
+            if a == e {
+                e.alpha(e.a / 4)
+            } else {
+                count += 1;
+                Rgba8::RED
+            }
+        })
+        .collect::<Vec<_>>();
+
+    (Image::new(pixels, actual.size), count)
+}
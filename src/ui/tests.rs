@@ -1,53 +1,18 @@
-use crate::platform::MouseButton;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
-use super::text::*;
-use super::widgets::{WidgetExt, ZStack};
-use super::*;
-
-struct Environment<'a, W, T> {
-    root: W,
-    layout_ctx: LayoutCtx<'a>,
-    ctx: Context<'a>,
-    env: Env,
-    data: PhantomData<T>,
-}
-
-impl<'a, T, W: Widget<T>> Environment<'a, W, T> {
-    fn new(
-        widget: fn() -> W,
-        store: &'a HashMap<TextureId, Image>,
-        fonts: &'a HashMap<FontId, Font>,
-    ) -> Self {
-        let ctx = Context::new(Point::default(), store);
-        let env = Env::default();
-        let layout_ctx = LayoutCtx::new(fonts);
-
-        Self {
-            root: widget(),
-            layout_ctx,
-            ctx,
-            env,
-            data: PhantomData,
-        }
-    }
+use crate::application::{Application, Script};
+use crate::platform::{self, InputState, LogicalPosition, LogicalSize, MouseButton, WindowEvent};
 
-    fn hover(&mut self, point: impl Into<Point2D>, data: &mut T) -> &mut Self {
-        let point = point.into();
-
-        self.ctx = Context::new(point, self.ctx.surfaces);
-        self.root
-            .event(&WidgetEvent::MouseMove(point), &self.ctx, data);
-        self
-    }
-
-    fn click(&mut self, data: &mut T) -> &mut Self {
-        self.root
-            .event(&WidgetEvent::MouseDown(MouseButton::Left), &self.ctx, data);
-        self.root
-            .event(&WidgetEvent::MouseUp(MouseButton::Left), &self.ctx, data);
-        self
-    }
-}
+use super::testing::{self, Environment};
+use super::widgets::{
+    CellAlignment, Container, Controller, CrossAxisAlignment, Either, Flex, Grid, GridItem, List,
+    MainAxisAlignment, Overlay, Scroll, SizedBox, TrackSize, ViewSwitcher, WidgetExt, ZStack,
+};
+use super::*;
 
 #[derive(Default, Debug, PartialEq, Eq)]
 struct Data {
@@ -100,15 +65,12 @@ fn simple_zstack() -> ZStack<(Data, Data)> {
 
 #[test]
 fn test_simple_zstack_hover() {
-    let (store, fonts) = (HashMap::new(), HashMap::new());
-    let mut e = Environment::new(simple_zstack, &store, &fonts);
+    let mut e = Environment::new(simple_zstack());
     let mut data: (Data, Data) = Default::default();
 
     crate::logger::init(log::Level::Debug).unwrap();
 
-    e.root.update(&e.ctx, &data);
-    e.root
-        .layout(Size::new(512., 512.), &e.layout_ctx, &data, &e.env);
+    e.update(&data).layout([512., 512.], &data);
 
     e.hover([64., 64.], &mut data);
     assert!(!data.1.hot);
@@ -125,12 +87,10 @@ fn test_simple_zstack_hover() {
 
 #[test]
 fn test_simple_zstack_click() {
-    let (store, fonts) = (HashMap::new(), HashMap::new());
-    let mut e = Environment::new(simple_zstack, &store, &fonts);
+    let mut e = Environment::new(simple_zstack());
     let mut data = Default::default();
 
-    e.root
-        .layout(Size::new(512., 512.), &e.layout_ctx, &data, &e.env);
+    e.layout([512., 512.], &data);
 
     e.hover([64., 64.], &mut data).click(&mut data);
     assert_eq!(data.1.clicks, 0);
@@ -147,12 +107,10 @@ fn test_simple_zstack_click() {
 
 #[test]
 fn test_simple_hstack_hover() {
-    let (store, fonts) = (HashMap::new(), HashMap::new());
-    let mut e = Environment::new(simple_hstack, &store, &fonts);
+    let mut e = Environment::new(simple_hstack());
     let mut data = Default::default();
 
-    e.root
-        .layout(Size::new(512., 512.), &e.layout_ctx, &data, &e.env);
+    e.layout([512., 512.], &data);
 
     e.hover([0., 0.], &mut data);
     assert!(!data.0.hot);
@@ -174,3 +132,1984 @@ fn test_simple_hstack_hover() {
     assert!(!data.1.hot);
     assert!(data.2.hot);
 }
+
+#[test]
+fn test_headless_zstack_click() {
+    let click = |state| WindowEvent::MouseInput {
+        state,
+        button: MouseButton::Left,
+        modifiers: Default::default(),
+    };
+    let script = Script::new(LogicalSize::new(512., 512.))
+        .frame([WindowEvent::CursorMoved {
+            position: LogicalPosition::new(256., 256.),
+        }])
+        .frame([click(InputState::Pressed), click(InputState::Released)])
+        .idle(1)
+        .frame([WindowEvent::Minimized]);
+
+    let (data, frames) = Application::new("test")
+        .headless(simple_zstack(), <(Data, Data)>::default(), script)
+        .unwrap();
+
+    assert_eq!(data.0.clicks, 1);
+    assert_eq!(data.1.clicks, 0);
+    assert!(data.0.hot);
+    assert_eq!(frames.len(), 4);
+
+    // While minimized, the last frame is repeated.
+    assert_eq!(frames[3].pixels, frames[2].pixels);
+
+    let frame = frames.last().unwrap();
+
+    assert_eq!(frame.size, Size::new(512, 512));
+    assert_eq!(frame.sample(Point2D::new(256, 256)), Some(&Rgba8::RED));
+    assert_eq!(frame.sample(Point2D::new(16, 16)), Some(&Rgba8::BLUE));
+}
+
+#[test]
+fn test_snapshot() {
+    let dir = std::env::temp_dir().join(format!("rgx-test-snapshot-{}", std::process::id()));
+    let path = dir.join("zstack.rgba");
+    let mut e = Environment::new(simple_zstack()).scale(0.5);
+    let mut data = Default::default();
+
+    e.layout([64., 64.], &data);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let image = e.capture(&data).unwrap();
+    image.write(std::fs::File::create(&path).unwrap()).unwrap();
+
+    assert_eq!(image.size, Size::new(32, 32));
+    e.snapshot(&data, &path).unwrap();
+
+    // Hovering doesn't change the appearance.
+    e.hover([32., 32.], &mut data);
+    e.snapshot(&data, &path).unwrap();
+
+    // A different size does.
+    e.layout([32., 32.], &data);
+    assert!(matches!(
+        e.snapshot(&data, &path),
+        Err(testing::Error::SizeMismatch { .. })
+    ));
+
+    // A different color does.
+    let mut e = Environment::new(center(Rgba8::GREEN.sized([64., 64.]))).scale(0.5);
+    e.layout([64., 64.], &());
+
+    match e.snapshot(&(), &path) {
+        Err(testing::Error::Mismatch { diff, pixels, .. }) => {
+            let diff = std::fs::read(diff).unwrap();
+            let diff = Image::try_from(diff.as_slice()).unwrap();
+
+            assert_eq!(pixels, 32 * 32);
+            assert_eq!(diff.sample(Point2D::new(0, 0)), Some(&Rgba8::RED));
+        }
+        other => panic!("unexpected result {other:?}"),
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_ui_needs_redraw() {
+    let mut ui = Ui::new(simple_zstack(), <(Data, Data)>::default())
+        .size(LogicalSize::new(512., 512.))
+        .scale(2.);
+
+    assert!(ui.needs_redraw());
+    ui.update(Duration::ZERO);
+    assert!(!ui.needs_redraw());
+
+    // Events that don't affect widgets don't require a redraw.
+    ui.handle_event(WindowEvent::Moved(LogicalPosition::new(8., 8.)));
+    assert!(!ui.needs_redraw());
+
+    ui.handle_event(WindowEvent::CursorMoved {
+        position: LogicalPosition::new(256., 256.),
+    });
+    assert!(ui.needs_redraw());
+    ui.update(Duration::ZERO);
+    assert!(!ui.needs_redraw());
+    assert!(ui.data().0.hot);
+
+    ui.request_redraw();
+    assert!(ui.needs_redraw());
+}
+
+#[derive(Default)]
+struct Timed;
+
+impl Widget<u32> for Timed {
+    fn paint(&mut self, _canvas: Canvas<'_>, _data: &u32) {}
+
+    fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut u32) -> ControlFlow<()> {
+        match event {
+            WidgetEvent::MouseDown(_) => {
+                ctx.request_timer(Duration::from_millis(100));
+            }
+            WidgetEvent::Timer(_) => {
+                *data += 1;
+                ctx.request_paint();
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+#[test]
+fn test_ui_timers() {
+    let mut ui = Ui::new(zstack((Timed, Timed)), 0).size(LogicalSize::new(64., 64.));
+
+    ui.update(Duration::ZERO);
+    assert_eq!(ui.timeout(), None);
+
+    ui.handle_event(WindowEvent::CursorMoved {
+        position: LogicalPosition::new(8., 8.),
+    });
+    ui.handle_event(WindowEvent::MouseInput {
+        state: InputState::Pressed,
+        button: MouseButton::Left,
+        modifiers: Default::default(),
+    });
+    ui.update(Duration::from_millis(10));
+    assert_eq!(ui.timeout(), Some(Duration::from_millis(100)));
+    assert!(!ui.needs_redraw());
+
+    ui.update(Duration::from_millis(60));
+    assert_eq!(ui.timeout(), Some(Duration::from_millis(40)));
+    assert_eq!(*ui.data(), 0);
+
+    // Each timer is only delivered to the widget that requested it.
+    ui.update(Duration::from_millis(60));
+    assert_eq!(ui.timeout(), None);
+    assert_eq!(*ui.data(), 2);
+    assert!(ui.needs_redraw());
+}
+
+const PING: Selector = Selector::new("ping");
+const ADD: Selector<u32> = Selector::new("add");
+const FORWARD: Selector<WidgetId> = Selector::new("forward");
+
+/// Counts the commands it receives.
+struct Receiver(usize);
+
+impl Widget<[u32; 3]> for Receiver {
+    fn paint(&mut self, _canvas: Canvas<'_>, _data: &[u32; 3]) {}
+
+    fn event(
+        &mut self,
+        event: &WidgetEvent,
+        ctx: &Context<'_>,
+        data: &mut [u32; 3],
+    ) -> ControlFlow<()> {
+        assert!(
+            !matches!(event, WidgetEvent::Route(_)),
+            "routed commands are delivered by pods"
+        );
+        if let WidgetEvent::Command(cmd) = event {
+            if cmd.is(PING) {
+                data[self.0] += 1;
+            } else if let Some(n) = cmd.get(ADD) {
+                data[self.0] += n;
+            } else if let Some(id) = cmd.get(FORWARD) {
+                ctx.submit_command(Command::from(PING).to(*id));
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+#[test]
+fn test_ext_event_sink() {
+    let mut ui = Ui::new(Receiver(0), [0; 3]).size(LogicalSize::new(64., 64.));
+    let sink = ui.sink();
+
+    ui.update(Duration::ZERO);
+    assert!(!ui.needs_redraw());
+
+    std::thread::spawn(move || {
+        sink.submit_command(ADD.with(2));
+        sink.update(|data| data[0] *= 10);
+    })
+    .join()
+    .unwrap();
+
+    assert!(ui.needs_redraw());
+    ui.update(Duration::ZERO);
+    assert!(!ui.needs_redraw());
+    // Updates are applied before commands are delivered.
+    assert_eq!(ui.data()[0], 2);
+
+    // Once the waker is cleared, events are queued without waking anything.
+    let wakes = Arc::new(AtomicUsize::new(0));
+    let sink = ui.sink();
+
+    sink.set_waker({
+        let wakes = wakes.clone();
+        move || {
+            wakes.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    sink.update(|data| data[1] += 1);
+    sink.clear_waker();
+    sink.update(|data| data[1] += 1);
+
+    assert_eq!(wakes.load(Ordering::SeqCst), 1);
+    assert_eq!(sink.len(), 2);
+}
+
+#[test]
+fn test_commands() {
+    let first = Pod::new(Receiver(0));
+    let second = Pod::new(Receiver(1));
+    let (first_id, second_id) = (first.id, second.id);
+    let group = Pod::new(hstack(vec![second.boxed(), Receiver(2).boxed()]));
+    let group_id = group.id;
+    let mut ui = Ui::new(zstack((first, group)), [0; 3]).size(LogicalSize::new(64., 64.));
+
+    ui.submit_command(PING);
+    ui.update(Duration::ZERO);
+    assert_eq!(ui.data(), &[1, 1, 1]);
+
+    ui.submit_command(Command::from(PING).to(first_id));
+    ui.update(Duration::ZERO);
+    assert_eq!(ui.data(), &[2, 1, 1]);
+
+    ui.submit_command(ADD.with(2).to(Target::Subtree(group_id)));
+    ui.update(Duration::ZERO);
+    assert_eq!(ui.data(), &[2, 3, 3]);
+
+    // Commands submitted by widgets are delivered on the next update.
+    ui.submit_command(FORWARD.with(second_id).to(first_id));
+    ui.update(Duration::ZERO);
+    assert_eq!(ui.data(), &[2, 3, 3]);
+    assert!(ui.needs_redraw());
+    ui.update(Duration::ZERO);
+    assert_eq!(ui.data(), &[2, 4, 3]);
+}
+
+#[test]
+fn test_commands_dynamic() {
+    // The receiver is rebuilt when the last counter changes.
+    let built = Rc::new(Cell::new(None));
+    let switcher = ViewSwitcher::new(|data: &[u32; 3]| data[2], {
+        let built = built.clone();
+        move |key, _| {
+            let pod = Pod::new(Receiver(*key as usize));
+            built.set(Some(pod.id));
+            pod.boxed()
+        }
+    });
+    let mut data = [0; 3];
+    let mut e = Environment::new(switcher);
+    let ping = |id| WidgetEvent::Route(Command::from(PING).to(id));
+
+    e.update(&data);
+    let first = built.get().unwrap();
+    e.event(&ping(first), &mut data);
+    assert_eq!(data, [1, 0, 0]);
+
+    // Commands are routed to widgets added after the first update, and not to
+    // widgets that were removed.
+    data[2] = 1;
+    e.update(&data);
+    let second = built.get().unwrap();
+    e.event(&ping(second), &mut data);
+    e.event(&ping(first), &mut data);
+    assert_eq!(data, [1, 1, 1]);
+}
+
+#[test]
+fn test_environment_requests() {
+    let first = Pod::new(Receiver(0));
+    let second = Pod::new(Receiver(1));
+    let (first_id, second_id) = (first.id, second.id);
+    let mut data = [0; 3];
+    let mut e = Environment::new(zstack((first, second)));
+
+    e.layout([64., 64.], &data);
+
+    // Commands submitted by widgets are delivered after the event.
+    e.event(
+        &WidgetEvent::Route(FORWARD.with(second_id).to(first_id)),
+        &mut data,
+    );
+    assert_eq!(data, [0, 1, 0]);
+    assert!(e.requests().commands.is_empty());
+
+    // Timers fire once their delay has elapsed.
+    let mut count = 0;
+    let mut e = Environment::new(Timed.sized([64., 64.]));
+
+    e.layout([64., 64.], &count);
+    e.hover([8., 8.], &mut count).click(&mut count);
+    e.tick(Duration::from_millis(60), &mut count);
+    assert_eq!(count, 0);
+    e.tick(Duration::from_millis(60), &mut count);
+    assert_eq!(count, 1);
+    assert!(e.requests().paint);
+}
+
+/// Records the characters it receives, and whether it has focus.
+struct Input(usize);
+
+impl Widget<[(String, bool); 3]> for Input {
+    fn paint(&mut self, _canvas: Canvas<'_>, _data: &[(String, bool); 3]) {}
+
+    fn event(
+        &mut self,
+        event: &WidgetEvent,
+        ctx: &Context<'_>,
+        data: &mut [(String, bool); 3],
+    ) -> ControlFlow<()> {
+        match event {
+            WidgetEvent::CharacterReceived(c, _) => data[self.0].0.push(*c),
+            WidgetEvent::Focus(focus) if ctx.has_focus() => data[self.0].1 = *focus,
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_focus() {
+    fn key(ui: &mut Ui<[(String, bool); 3]>, key: platform::Key, shift: bool) {
+        ui.handle_event(WindowEvent::KeyboardInput(platform::KeyboardInput {
+            state: InputState::Pressed,
+            key: Some(key),
+            modifiers: platform::ModifiersState {
+                shift,
+                ..Default::default()
+            },
+        }));
+        ui.update(Duration::ZERO);
+    }
+    fn type_char(ui: &mut Ui<[(String, bool); 3]>, c: char) {
+        ui.handle_event(WindowEvent::ReceivedCharacter(c, Default::default()));
+        ui.update(Duration::ZERO);
+    }
+    fn focused(ui: &Ui<[(String, bool); 3]>) -> Vec<bool> {
+        ui.data().iter().map(|(_, f)| *f).collect()
+    }
+
+    let items = (0..3)
+        .map(|i| Input(i).sized([16., 16.]).boxed())
+        .collect::<Vec<_>>();
+    let mut ui = Ui::new(hstack(items), Default::default()).size(LogicalSize::new(48., 16.));
+
+    ui.update(Duration::ZERO);
+    assert_eq!(ui.focused(), None);
+
+    // Without focus, keyboard events aren't delivered.
+    type_char(&mut ui, 'a');
+    assert!(ui.data().iter().all(|(s, _)| s.is_empty()));
+
+    key(&mut ui, platform::Key::Tab, false);
+    assert_eq!(focused(&ui), [true, false, false]);
+    type_char(&mut ui, 'b');
+    assert_eq!(ui.data()[0].0, "b");
+    assert_eq!(ui.data()[1].0, "");
+
+    key(&mut ui, platform::Key::Tab, false);
+    key(&mut ui, platform::Key::Tab, false);
+    assert_eq!(focused(&ui), [false, false, true]);
+
+    // Focus wraps around in both directions.
+    key(&mut ui, platform::Key::Tab, false);
+    assert_eq!(focused(&ui), [true, false, false]);
+    key(&mut ui, platform::Key::Tab, true);
+    assert_eq!(focused(&ui), [false, false, true]);
+
+    // Clicking a widget focuses it.
+    ui.handle_event(WindowEvent::CursorMoved {
+        position: LogicalPosition::new(20., 8.),
+    });
+    ui.handle_event(WindowEvent::MouseInput {
+        state: InputState::Pressed,
+        button: MouseButton::Left,
+        modifiers: Default::default(),
+    });
+    ui.update(Duration::ZERO);
+    assert_eq!(focused(&ui), [false, true, false]);
+    type_char(&mut ui, 'c');
+    assert_eq!(ui.data()[1].0, "c");
+    assert_eq!(ui.data()[2].0, "");
+}
+
+/// Counts the `Tab` keys it handles while focused, and how often it lost focus.
+struct Indent(Rc<Cell<bool>>);
+
+impl Widget<(u32, u32)> for Indent {
+    fn paint(&mut self, _canvas: Canvas<'_>, _data: &(u32, u32)) {}
+
+    fn event(
+        &mut self,
+        event: &WidgetEvent,
+        _ctx: &Context<'_>,
+        data: &mut (u32, u32),
+    ) -> ControlFlow<()> {
+        match event {
+            WidgetEvent::KeyDown {
+                key: platform::Key::Tab,
+                ..
+            } => {
+                data.0 += 1;
+                return ControlFlow::Break(());
+            }
+            WidgetEvent::Focus(false) => data.1 += 1,
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn accepts_focus(&self) -> bool {
+        self.0.get()
+    }
+}
+
+#[test]
+fn test_focus_tab() {
+    fn tab(ui: &mut Ui<(u32, u32)>) {
+        ui.handle_event(WindowEvent::KeyboardInput(platform::KeyboardInput {
+            state: InputState::Pressed,
+            key: Some(platform::Key::Tab),
+            modifiers: Default::default(),
+        }));
+        ui.update(Duration::ZERO);
+    }
+    let focusable = Rc::new(Cell::new(true));
+    let mut ui = Ui::new(Indent(focusable.clone()).sized([16., 16.]), (0, 0))
+        .size(LogicalSize::new(16., 16.));
+
+    ui.update(Duration::ZERO);
+
+    // Without focus, `Tab` moves focus.
+    tab(&mut ui);
+    assert!(ui.focused().is_some());
+    assert_eq!(*ui.data(), (0, 0));
+
+    // The focused widget may handle `Tab` itself.
+    tab(&mut ui);
+    assert!(ui.focused().is_some());
+    assert_eq!(*ui.data(), (1, 0));
+
+    // Focus is lost when the widget stops accepting it.
+    focusable.set(false);
+    ui.update(Duration::ZERO);
+    assert_eq!(ui.focused(), None);
+    assert_eq!(*ui.data(), (1, 1));
+
+    // Focus moves on the next update, once the focus chain is collected.
+    ui.focus_next(true);
+    ui.update(Duration::ZERO);
+    assert_eq!(ui.focused(), None);
+
+    focusable.set(true);
+    ui.focus_next(true);
+    assert_eq!(ui.focused(), None);
+    ui.update(Duration::ZERO);
+    assert!(ui.focused().is_some());
+}
+
+/// Logs the phases in which it sees `MouseDown` events, optionally stopping them
+/// in the capture phase.
+struct Log(&'static str, bool);
+
+impl<W: Widget<Vec<(&'static str, Phase)>>> Controller<Vec<(&'static str, Phase)>, W> for Log {
+    fn capture(
+        &mut self,
+        child: &mut W,
+        event: &WidgetEvent,
+        ctx: &Context<'_>,
+        data: &mut Vec<(&'static str, Phase)>,
+    ) -> ControlFlow<()> {
+        if let WidgetEvent::MouseDown(_) = event {
+            data.push((self.0, ctx.phase));
+
+            if self.1 {
+                return ControlFlow::Break(());
+            }
+        }
+        child.capture(event, ctx, data)
+    }
+
+    fn event(
+        &mut self,
+        child: &mut W,
+        event: &WidgetEvent,
+        ctx: &Context<'_>,
+        data: &mut Vec<(&'static str, Phase)>,
+    ) -> ControlFlow<()> {
+        child.event(event, ctx, data)?;
+
+        if let WidgetEvent::MouseDown(_) = event {
+            data.push((self.0, ctx.phase));
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+#[test]
+fn test_event_phases() {
+    let tree = |stop| {
+        Control::new(
+            center(Control::new(
+                Control::new(Rgba8::RED, Log("leaf", false)).sized([16., 16.]),
+                Log("mid", stop),
+            )),
+            Log("outer", false),
+        )
+    };
+    let mut data = Vec::new();
+    let mut e = Environment::new(tree(false));
+
+    e.layout([64., 64.], &data).hover([32., 32.], &mut data);
+    e.press(&mut data);
+
+    assert_eq!(
+        data,
+        [
+            ("outer", Phase::Capture),
+            ("mid", Phase::Capture),
+            ("leaf", Phase::Capture),
+            ("leaf", Phase::Target),
+            ("mid", Phase::Bubble),
+            ("outer", Phase::Bubble),
+        ]
+    );
+
+    // Stopping the event while capturing hides it from descendants and ancestors.
+    let mut data = Vec::new();
+    let mut e = Environment::new(tree(true));
+
+    e.layout([64., 64.], &data).hover([32., 32.], &mut data);
+    e.press(&mut data);
+
+    assert_eq!(data, [("outer", Phase::Capture), ("mid", Phase::Capture)]);
+}
+
+#[test]
+fn test_nested_click() {
+    let widget = Rgba8::BLUE
+        .sized([64., 64.])
+        .on_click(|_, data: &mut (Data, Data)| data.1.clicks += 1);
+    let widget = zstack((
+        widget,
+        center(
+            Rgba8::RED
+                .sized([16., 16.])
+                .on_click(|_, data: &mut (Data, Data)| data.0.clicks += 1),
+        )
+        .on_click(|_, data: &mut (Data, Data)| data.1.clicks += 1),
+    ));
+    let mut data = <(Data, Data)>::default();
+    let mut e = Environment::new(widget);
+
+    e.layout([64., 64.], &data);
+    e.hover([32., 32.], &mut data).click(&mut data);
+    assert_eq!((data.0.clicks, data.1.clicks), (1, 0));
+
+    e.hover([4., 4.], &mut data).click(&mut data);
+    assert_eq!((data.0.clicks, data.1.clicks), (1, 1));
+}
+
+/// Records the pointer events it receives.
+struct Dragger;
+
+impl Widget<Vec<(&'static str, Point, Vector)>> for Dragger {
+    fn paint(&mut self, _canvas: Canvas<'_>, _data: &Vec<(&'static str, Point, Vector)>) {}
+
+    fn event(
+        &mut self,
+        event: &WidgetEvent,
+        _ctx: &Context<'_>,
+        data: &mut Vec<(&'static str, Point, Vector)>,
+    ) -> ControlFlow<()> {
+        match event {
+            WidgetEvent::MouseMove(p) => data.push(("move", *p, Vector::ZERO)),
+            WidgetEvent::DragStart(d) => data.push(("start", d.start, d.offset())),
+            WidgetEvent::DragMove(d) => data.push(("drag", d.position, d.delta)),
+            WidgetEvent::DragEnd(d) => data.push(("end", d.position, d.offset())),
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+#[test]
+fn test_drag() {
+    let mut data = Vec::new();
+    let mut e = Environment::new(zstack((Rgba8::BLUE, center(Dragger.sized([16., 16.])))));
+
+    e.layout([64., 64.], &data).hover([32., 32.], &mut data);
+    e.press(&mut data);
+    e.hover([36., 32.], &mut data);
+    // The widget keeps receiving moves outside of its bounds.
+    e.hover([60., 32.], &mut data);
+    e.release(&mut data);
+    // Once released, it doesn't.
+    e.hover([50., 32.], &mut data);
+
+    assert_eq!(
+        data,
+        [
+            ("move", Point::new(12., 8.), Vector::ZERO),
+            ("start", Point::new(8., 8.), Vector::new(4., 0.)),
+            ("drag", Point::new(12., 8.), Vector::new(4., 0.)),
+            ("move", Point::new(36., 8.), Vector::ZERO),
+            ("drag", Point::new(36., 8.), Vector::new(24., 0.)),
+            ("end", Point::new(36., 8.), Vector::new(28., 0.)),
+        ]
+    );
+}
+
+#[test]
+fn test_double_click() {
+    let mut data = Vec::new();
+    let mut e = Environment::new(Rgba8::RED.on_clicks(|click, _, data: &mut Vec<u32>| {
+        data.push(click.count);
+    }));
+    let tick = |e: &mut Environment<_, _>, data: &mut _, ms| {
+        e.tick(Duration::from_millis(ms), data);
+    };
+
+    e.layout([64., 64.], &data).hover([8., 8.], &mut data);
+    e.click(&mut data);
+    tick(&mut e, &mut data, 100);
+    e.click(&mut data);
+    tick(&mut e, &mut data, 100);
+    e.click(&mut data);
+    assert_eq!(data, [1, 2, 3]);
+
+    // Too slow.
+    tick(&mut e, &mut data, 600);
+    e.click(&mut data);
+    // Too far.
+    e.hover([32., 32.], &mut data).click(&mut data);
+    tick(&mut e, &mut data, 100);
+    e.click(&mut data);
+    assert_eq!(data, [1, 2, 3, 1, 1, 2]);
+}
+
+#[test]
+fn test_long_press() {
+    let mut data = Data::default();
+    let mut e = Environment::new(
+        Rgba8::RED
+            .on_long_press(|_, data: &mut Data| data.hot = true)
+            .on_click(|_, data: &mut Data| data.clicks += 1),
+    );
+    let tick = |e: &mut Environment<_, _>, data: &mut _, ms| {
+        e.tick(Duration::from_millis(ms), data);
+    };
+
+    e.layout([64., 64.], &data).hover([8., 8.], &mut data);
+
+    // A short press is a click.
+    e.press(&mut data);
+    tick(&mut e, &mut data, 300);
+    e.release(&mut data);
+    assert_eq!(
+        data,
+        Data {
+            clicks: 1,
+            hot: false
+        }
+    );
+
+    // A long press isn't.
+    e.press(&mut data);
+    tick(&mut e, &mut data, 300);
+    tick(&mut e, &mut data, 300);
+    assert!(data.hot);
+    e.release(&mut data);
+    assert_eq!(data.clicks, 1);
+}
+
+/// Records the mouse button presses it receives.
+struct Presses;
+
+impl Widget<Vec<MouseEvent>> for Presses {
+    fn paint(&mut self, _canvas: Canvas<'_>, _data: &Vec<MouseEvent>) {}
+
+    fn event(
+        &mut self,
+        event: &WidgetEvent,
+        _ctx: &Context<'_>,
+        data: &mut Vec<MouseEvent>,
+    ) -> ControlFlow<()> {
+        if let WidgetEvent::MouseDown(mouse) = event {
+            data.push(*mouse);
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+#[test]
+fn test_mouse_events() {
+    let shift = platform::ModifiersState {
+        shift: true,
+        ..Default::default()
+    };
+    let mut ui =
+        Ui::new(center(Presses.sized([16., 16.])), Vec::new()).size(LogicalSize::new(64., 64.));
+    let press = |ui: &mut Ui<_>, button, modifiers, delta| {
+        for state in [InputState::Pressed, InputState::Released] {
+            ui.handle_event(WindowEvent::MouseInput {
+                state,
+                button,
+                modifiers,
+            });
+        }
+        ui.update(Duration::from_millis(delta));
+    };
+
+    ui.handle_event(WindowEvent::CursorMoved {
+        position: LogicalPosition::new(26., 30.),
+    });
+    ui.update(Duration::ZERO);
+
+    press(&mut ui, MouseButton::Left, shift, 100);
+    press(&mut ui, MouseButton::Left, Default::default(), 600);
+    press(&mut ui, MouseButton::Right, Default::default(), 100);
+    press(&mut ui, MouseButton::Right, Default::default(), 100);
+
+    // Positions are in widget coordinates.
+    let position = Point::new(2., 6.);
+
+    assert_eq!(
+        ui.data(),
+        &[
+            MouseEvent::new(MouseButton::Left, position).modifiers(shift),
+            MouseEvent::new(MouseButton::Left, position).count(2),
+            MouseEvent::new(MouseButton::Right, position),
+            MouseEvent::new(MouseButton::Right, position).count(2),
+        ]
+    );
+}
+
+#[test]
+fn test_vstack() {
+    let mut e = Environment::new(
+        vstack((Rgba8::RED.sized([16., 16.]), Rgba8::GREEN.sized([8., 16.]))).spacing(4.),
+    );
+    e.layout([64., 64.], &());
+
+    let image = e.capture(&()).unwrap();
+    let pixel = |x, y| *image.sample([x, y].into()).unwrap();
+
+    assert_eq!(pixel(15, 15), Rgba8::RED);
+    assert_eq!(pixel(4, 18), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(7, 20), Rgba8::GREEN);
+    assert_eq!(pixel(12, 20), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(4, 36), Rgba8::TRANSPARENT);
+}
+
+#[test]
+fn test_flex() {
+    // Flexible children fill the space left over.
+    let mut e = Environment::new(
+        Flex::column()
+            .child(Rgba8::RED.sized([64., 16.]))
+            .flex_child(Rgba8::WHITE, 1.)
+            .flex_child(Rgba8::GREEN, 3.)
+            .child(Rgba8::BLUE.sized([64., 8.]))
+            .spacing(4.),
+    );
+    e.layout([64., 64.], &());
+
+    let image = e.capture(&()).unwrap();
+    let column = (0..64)
+        .map(|y| *image.sample([32, y].into()).unwrap())
+        .collect::<Vec<_>>();
+
+    // 16 + 4 + 7 + 4 + 21 + 4 + 8 = 64.
+    assert_eq!(column[0..16], [Rgba8::RED; 16]);
+    assert_eq!(column[16..20], [Rgba8::TRANSPARENT; 4]);
+    assert_eq!(column[20..27], [Rgba8::WHITE; 7]);
+    assert_eq!(column[27..31], [Rgba8::TRANSPARENT; 4]);
+    assert_eq!(column[31..52], [Rgba8::GREEN; 21]);
+    assert_eq!(column[52..56], [Rgba8::TRANSPARENT; 4]);
+    assert_eq!(column[56..64], [Rgba8::BLUE; 8]);
+
+    // Without flexible children, alignment distributes the space left over.
+    let mut e = Environment::new(
+        Flex::row()
+            .child(Rgba8::RED.sized([8., 8.]))
+            .child(Rgba8::BLUE.sized([8., 16.]))
+            .main_axis_alignment(MainAxisAlignment::SpaceEvenly)
+            .cross_axis_alignment(CrossAxisAlignment::Center),
+    );
+    e.layout([40., 16.], &());
+
+    let image = e.capture(&()).unwrap();
+    let pixel = |x, y| *image.sample([x, y].into()).unwrap();
+
+    assert_eq!(pixel(8, 4), Rgba8::RED);
+    assert_eq!(pixel(15, 11), Rgba8::RED);
+    assert_eq!(pixel(8, 3), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(7, 4), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(24, 0), Rgba8::BLUE);
+    assert_eq!(pixel(31, 15), Rgba8::BLUE);
+    assert_eq!(pixel(32, 8), Rgba8::TRANSPARENT);
+
+    // Without a bound on the main axis, flexible children get their own size.
+    let mut e = Environment::new(Scroll::vertical(
+        Flex::column()
+            .child(Rgba8::RED.sized([32., 16.]))
+            .flex_child(Rgba8::GREEN.sized([32., 8.]), 1.)
+            .child(Rgba8::BLUE.sized([32., 16.])),
+    ));
+    e.layout([32., 64.], &());
+
+    let image = e.capture(&()).unwrap();
+    let column = (0..64)
+        .map(|y| *image.sample([0, y].into()).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(column[0..16], [Rgba8::RED; 16]);
+    assert_eq!(column[16..24], [Rgba8::GREEN; 8]);
+    assert_eq!(column[24..40], [Rgba8::BLUE; 16]);
+    assert_eq!(column[40..64], [Rgba8::TRANSPARENT; 24]);
+}
+
+/// Records the constraints it's laid out with.
+struct Probe(std::rc::Rc<std::cell::RefCell<Vec<BoxConstraints>>>);
+
+impl Widget<()> for Probe {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        _ctx: &LayoutCtx<'_>,
+        _data: &(),
+        _env: &Env,
+    ) -> Size {
+        self.0.borrow_mut().push(constraints);
+        // Ask for more than we can have.
+        Size::new(1000., 1.)
+    }
+
+    fn paint(&mut self, _canvas: Canvas<'_>, _data: &()) {}
+}
+
+#[test]
+fn test_constraints() {
+    let log = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let mut e = Environment::new(vstack((
+        Probe(log.clone()).sized([16., 16.]),
+        SizedBox::new(Probe(log.clone())).width(16.),
+        SizedBox::new(Probe(log.clone())).height(128.),
+    )));
+    e.layout([64., 64.], &());
+
+    assert_eq!(
+        log.borrow().as_slice(),
+        [
+            // Sized boxes enforce their size.
+            BoxConstraints::tight([16., 16.]),
+            // Dimensions that aren't set are left to the child, which is clamped
+            // to a height of `1` by its pod.
+            BoxConstraints::new([16., 0.], [16., 48.]),
+            // Sizes are clamped to the parent constraints.
+            BoxConstraints::new([0., 47.], [64., 47.]),
+        ]
+    );
+
+    // Inconsistent constraints don't panic, the maximum wins.
+    let c = BoxConstraints {
+        min: Size::new(32., 32.),
+        max: Size::new(16., 16.),
+    };
+    assert_eq!(c.constrain([8., 64.]), Size::new(16., 16.));
+
+    // Aligned widgets wrap their child in unbounded dimensions.
+    let graphics = Graphics::default();
+    let mut align = Pod::new(center(Rgba8::RED.sized([16., 16.])));
+    let size = align.layout(
+        BoxConstraints::new([0., 0.], [f32::INFINITY, 64.]),
+        &LayoutCtx::new(&graphics.fonts, &graphics.textures),
+        &(),
+        &Env::default(),
+    );
+    assert_eq!(size, Size::new(16., 64.));
+}
+
+#[test]
+fn test_container() {
+    // Padding is added around the child.
+    let mut e = Environment::new(
+        align(Rgba8::RED.sized([16., 16.]).padding([4., 8.]))
+            .top(0.)
+            .left(0.),
+    );
+    e.layout([48., 48.], &());
+
+    let image = e.capture(&()).unwrap();
+    let pixel = |x, y| *image.sample([x, y].into()).unwrap();
+
+    assert_eq!(pixel(8, 4), Rgba8::RED);
+    assert_eq!(pixel(23, 19), Rgba8::RED);
+    assert_eq!(pixel(7, 4), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(8, 3), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(24, 19), Rgba8::TRANSPARENT);
+
+    // The background fills the padding, and the border is painted over it.
+    let mut e = Environment::new(
+        align(
+            Container::new(Rgba8::RED.sized([16., 16.]))
+                .padding(8.)
+                .background(Rgba8::WHITE)
+                .border(Stroke::new(2., Rgba8::BLUE).inside()),
+        )
+        .top(0.)
+        .left(0.),
+    );
+    e.layout([48., 48.], &());
+
+    let image = e.capture(&()).unwrap();
+    let pixel = |x, y| *image.sample([x, y].into()).unwrap();
+
+    assert_eq!(pixel(0, 0), Rgba8::BLUE);
+    assert_eq!(pixel(31, 31), Rgba8::BLUE);
+    assert_eq!(pixel(2, 2), Rgba8::WHITE);
+    assert_eq!(pixel(29, 16), Rgba8::WHITE);
+    assert_eq!(pixel(8, 8), Rgba8::RED);
+    assert_eq!(pixel(23, 23), Rgba8::RED);
+    assert_eq!(pixel(32, 32), Rgba8::TRANSPARENT);
+
+    // Outside borders are drawn around the container.
+    let mut e = Environment::new(
+        align(
+            Container::new(Rgba8::RED.sized([16., 16.]))
+                .background(Rgba8::WHITE)
+                .border(Stroke::new(2., Rgba8::BLUE)),
+        )
+        .top(4.)
+        .left(4.),
+    );
+    e.layout([48., 48.], &());
+
+    let image = e.capture(&()).unwrap();
+    let pixel = |x, y| *image.sample([x, y].into()).unwrap();
+
+    assert_eq!(pixel(1, 1), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(2, 2), Rgba8::BLUE);
+    assert_eq!(pixel(3, 12), Rgba8::BLUE);
+    assert_eq!(pixel(4, 4), Rgba8::RED);
+    assert_eq!(pixel(19, 19), Rgba8::RED);
+    assert_eq!(pixel(21, 21), Rgba8::BLUE);
+    assert_eq!(pixel(22, 22), Rgba8::TRANSPARENT);
+
+    // Rounded corners leave the corner pixels empty.
+    let mut e = Environment::new(
+        align(
+            Container::new(Rgba8::RED.sized([16., 16.]))
+                .padding(8.)
+                .background(Rgba8::WHITE)
+                .radius(8.),
+        )
+        .top(0.)
+        .left(0.),
+    );
+    e.layout([48., 48.], &());
+
+    let image = e.capture(&()).unwrap();
+    let pixel = |x, y| *image.sample([x, y].into()).unwrap();
+
+    assert_eq!(pixel(0, 0), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(31, 31), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(0, 16), Rgba8::WHITE);
+    assert_eq!(pixel(16, 0), Rgba8::WHITE);
+    assert_eq!(pixel(4, 4), Rgba8::WHITE);
+    assert_eq!(pixel(16, 16), Rgba8::RED);
+}
+
+#[test]
+fn test_grid() {
+    let mut e = Environment::new(
+        align(
+            Grid::new()
+                .columns([TrackSize::Fixed(8.), TrackSize::Auto])
+                .spacing(2.)
+                .child(Rgba8::RED.sized([4., 4.]))
+                .child(Rgba8::GREEN.sized([12., 6.]))
+                .item(
+                    GridItem::new(Rgba8::BLUE.sized([1., 1.]))
+                        .span(1, 2)
+                        .align(CellAlignment::Fill, CellAlignment::Fill),
+                )
+                .item(
+                    GridItem::new(Rgba8::WHITE.sized([4., 4.]))
+                        .at(2, 1)
+                        .align(CellAlignment::End, CellAlignment::Center),
+                )
+                .cell(2, 0, Rgba8::BLACK.sized([2., 10.])),
+        )
+        .top(0.)
+        .left(0.),
+    );
+    e.layout([32., 32.], &());
+
+    // Columns are 8 and 12 wide, rows are 6, 1 and 10 high, with 2 spacing.
+    let image = e.capture(&()).unwrap();
+    let pixel = |x, y| *image.sample([x, y].into()).unwrap();
+
+    assert_eq!(pixel(0, 0), Rgba8::RED);
+    assert_eq!(pixel(4, 0), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(10, 0), Rgba8::GREEN);
+    assert_eq!(pixel(21, 5), Rgba8::GREEN);
+    assert_eq!(pixel(0, 6), Rgba8::TRANSPARENT);
+    // The spanning item fills both columns.
+    assert_eq!(pixel(0, 8), Rgba8::BLUE);
+    assert_eq!(pixel(21, 8), Rgba8::BLUE);
+    assert_eq!(pixel(0, 9), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(0, 11), Rgba8::BLACK);
+    assert_eq!(pixel(1, 20), Rgba8::BLACK);
+    assert_eq!(pixel(2, 11), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(18, 14), Rgba8::WHITE);
+    assert_eq!(pixel(21, 17), Rgba8::WHITE);
+    assert_eq!(pixel(17, 14), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(18, 13), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(22, 0), Rgba8::TRANSPARENT);
+
+    // Automatic placement doesn't depend on the order in which the grid is configured.
+    let grid = Grid::<()>::new()
+        .child(Rgba8::RED)
+        .child(Rgba8::GREEN)
+        .child(Rgba8::BLUE)
+        .columns([TrackSize::Auto; 2]);
+    assert_eq!((grid.row_count(), grid.column_count()), (2, 2));
+}
+
+#[test]
+fn test_grid_fill() {
+    let mut e = Environment::new(
+        align(
+            Grid::new()
+                .columns([TrackSize::Fixed(8.), TrackSize::Auto])
+                .rows([TrackSize::Fixed(4.)])
+                .spacing(2.)
+                .item(GridItem::new(Rgba8::RED).align(CellAlignment::Fill, CellAlignment::Fill))
+                .child(Rgba8::GREEN)
+                .cell(1, 1, Rgba8::BLUE.sized([6., 6.])),
+        )
+        .top(0.)
+        .left(0.),
+    );
+    e.layout([32., 32.], &());
+
+    // Children that fill their cell don't grow the tracks, or overflow them: columns
+    // are 8 and 6 wide, and rows are 4 and 6 high.
+    let image = e.capture(&()).unwrap();
+    let pixel = |x, y| *image.sample([x, y].into()).unwrap();
+
+    assert_eq!(pixel(0, 0), Rgba8::RED);
+    assert_eq!(pixel(7, 3), Rgba8::RED);
+    assert_eq!(pixel(8, 0), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(0, 4), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(10, 0), Rgba8::GREEN);
+    assert_eq!(pixel(15, 3), Rgba8::GREEN);
+    assert_eq!(pixel(16, 0), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(10, 6), Rgba8::BLUE);
+    assert_eq!(pixel(15, 11), Rgba8::BLUE);
+    assert_eq!(pixel(16, 12), Rgba8::TRANSPARENT);
+}
+
+#[test]
+fn test_hstack_wrap() {
+    let mut e = Environment::new(
+        align(
+            hstack((
+                Rgba8::RED.sized([12., 8.]),
+                Rgba8::GREEN.sized([12., 4.]),
+                Rgba8::BLUE.sized([12., 6.]),
+            ))
+            .spacing(2.)
+            .background(Rgba8::WHITE),
+        )
+        .top(0.)
+        .left(0.),
+    );
+    e.layout([28., 28.], &());
+
+    // The third child wraps onto a second row, below the tallest child of the first,
+    // and the stack fits both rows.
+    let image = e.capture(&()).unwrap();
+    let pixel = |x, y| *image.sample([x, y].into()).unwrap();
+
+    assert_eq!(pixel(0, 0), Rgba8::RED);
+    assert_eq!(pixel(0, 7), Rgba8::RED);
+    assert_eq!(pixel(14, 0), Rgba8::GREEN);
+    assert_eq!(pixel(0, 8), Rgba8::BLUE);
+    assert_eq!(pixel(11, 13), Rgba8::BLUE);
+    assert_eq!(pixel(25, 13), Rgba8::WHITE);
+    assert_eq!(pixel(26, 0), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(0, 14), Rgba8::TRANSPARENT);
+}
+
+#[test]
+fn test_scroll() {
+    let colors = [Rgba8::RED, Rgba8::GREEN, Rgba8::BLUE, Rgba8::WHITE];
+    let mut column = Flex::column();
+
+    for (i, color) in colors.into_iter().enumerate() {
+        column.push(
+            color
+                .sized([32., 16.])
+                .on_click(move |_, clicked: &mut Vec<usize>| clicked.push(i)),
+            0.,
+        );
+    }
+    let mut clicked = Vec::new();
+    let mut e = Environment::new(
+        align(Scroll::vertical(column).sized([32., 32.]))
+            .top(0.)
+            .left(0.),
+    );
+    e.layout([48., 48.], &clicked);
+
+    let pixel = |e: &mut Environment<_, _>, x, y| {
+        *e.capture(&Vec::new())
+            .unwrap()
+            .sample([x, y].into())
+            .unwrap()
+    };
+    assert_eq!(pixel(&mut e, 0, 0), Rgba8::RED);
+    assert_eq!(pixel(&mut e, 0, 16), Rgba8::GREEN);
+    // The content is clipped to the viewport.
+    assert_eq!(pixel(&mut e, 0, 32), Rgba8::TRANSPARENT);
+
+    // Scroll down by one step.
+    e.hover([4., 4.], &mut clicked).event(
+        &WidgetEvent::MouseScroll(platform::LogicalDelta { x: 0., y: -1. }),
+        &mut clicked,
+    );
+    assert_eq!(pixel(&mut e, 0, 0), Rgba8::GREEN);
+    assert_eq!(pixel(&mut e, 0, 12), Rgba8::BLUE);
+
+    // Events are translated by the scroll offset.
+    e.hover([4., 4.], &mut clicked).click(&mut clicked);
+    e.hover([4., 12.], &mut clicked).click(&mut clicked);
+    assert_eq!(clicked, vec![1, 2]);
+
+    // Scrolling stops at the end of the content.
+    e.event(
+        &WidgetEvent::MouseScroll(platform::LogicalDelta { x: 0., y: -8. }),
+        &mut clicked,
+    );
+    assert_eq!(pixel(&mut e, 0, 0), Rgba8::BLUE);
+    assert_eq!(pixel(&mut e, 0, 31), Rgba8::WHITE);
+
+    // The scrollbar thumb is half the track, and can be dragged back up.
+    // Clicks on the scrollbar don't reach the content.
+    clicked.clear();
+    e.hover([29., 20.], &mut clicked).press(&mut clicked);
+    e.hover([29., 4.], &mut clicked).release(&mut clicked);
+    assert_eq!(pixel(&mut e, 0, 0), Rgba8::RED);
+    assert!(clicked.is_empty());
+
+    // The content isn't hovered outside of the viewport.
+    e.hover([4., 40.], &mut clicked).click(&mut clicked);
+    assert!(clicked.is_empty());
+}
+
+#[test]
+fn test_clip() {
+    let widget = painter(|mut canvas, _: &()| {
+        let mut outer = canvas.transform(Transform::translate(Vector::new(4., 4.)));
+        let mut inner = outer.clip(Rect::new([0., 0.], [8., 8.]));
+        // Nested clips are intersected.
+        let mut inner = inner.clip(Rect::new([4., 2.], [8., 4.]));
+
+        inner.fill(Rect::new([0., 0.], [16., 16.]), Rgba8::RED);
+    });
+    let mut e = Environment::new(widget);
+    e.layout([16., 16.], &());
+
+    let image = e.capture(&()).unwrap();
+    let pixel = |x, y| *image.sample([x, y].into()).unwrap();
+
+    // The clips are in canvas coordinates, and are transformed with the canvas.
+    assert_eq!(pixel(8, 6), Rgba8::RED);
+    assert_eq!(pixel(11, 9), Rgba8::RED);
+    assert_eq!(pixel(7, 6), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(8, 5), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(12, 6), Rgba8::TRANSPARENT);
+    assert_eq!(pixel(8, 10), Rgba8::TRANSPARENT);
+}
+
+#[test]
+fn test_overlay() {
+    /// Toggles a popup when pressed.
+    struct Opener {
+        popup: Option<OverlayId>,
+        modal: bool,
+    }
+
+    impl Widget<Vec<&'static str>> for Opener {
+        fn layout(
+            &mut self,
+            constraints: BoxConstraints,
+            _ctx: &LayoutCtx<'_>,
+            _data: &Vec<&'static str>,
+            _env: &Env,
+        ) -> Size {
+            constraints.constrain([8., 8.])
+        }
+
+        fn paint(&mut self, mut canvas: Canvas<'_>, _data: &Vec<&'static str>) {
+            canvas.fill(Rect::new([0., 0.], [8., 8.]), Rgba8::RED);
+        }
+
+        fn event(
+            &mut self,
+            event: &WidgetEvent,
+            ctx: &Context<'_>,
+            _data: &mut Vec<&'static str>,
+        ) -> ControlFlow<()> {
+            if let WidgetEvent::MouseDown(_) = event {
+                if let Some(id) = self.popup.take() {
+                    ctx.hide_overlay(id);
+                } else {
+                    let popup = Rgba8::BLUE
+                        .sized([16., 16.])
+                        .on_click(|_, log: &mut Vec<&'static str>| log.push("popup"));
+
+                    self.popup = Some(if self.modal {
+                        ctx.show_modal(popup, [40., 40.])
+                    } else {
+                        ctx.show_overlay(popup, [4., 4.])
+                    });
+                }
+                return ControlFlow::Break(());
+            }
+            ControlFlow::Continue(())
+        }
+    }
+
+    let widget = |modal| {
+        // The opener is clipped by a scroll container, and followed by a sibling.
+        let opener = Scroll::new(Opener { popup: None, modal }).scrollbars(false);
+        let sibling = Rgba8::GREEN
+            .sized([16., 16.])
+            .on_click(|_, log: &mut Vec<&'static str>| log.push("sibling"));
+
+        Overlay::new(
+            align(hstack((opener, sibling)).padding(4.))
+                .top(0.)
+                .left(0.),
+        )
+    };
+    let mut log = Vec::new();
+    let mut e = Environment::new(widget(false));
+    e.layout([32., 32.], &log);
+
+    let pixel = |e: &mut Environment<_, _>, x, y| {
+        *e.capture(&Vec::new())
+            .unwrap()
+            .sample([x, y].into())
+            .unwrap()
+    };
+    assert_eq!(pixel(&mut e, 20, 12), Rgba8::GREEN);
+
+    // The popup is positioned relative to the opener, and painted above the sibling,
+    // outside of the opener's clip.
+    e.hover([6., 6.], &mut log).click(&mut log);
+    e.layout([32., 32.], &log);
+    assert_eq!(e.root.len(), 1);
+    assert_eq!(pixel(&mut e, 20, 12), Rgba8::BLUE);
+    assert_eq!(pixel(&mut e, 22, 22), Rgba8::BLUE);
+    assert_eq!(pixel(&mut e, 7, 7), Rgba8::RED);
+    assert_eq!(pixel(&mut e, 26, 6), Rgba8::GREEN);
+
+    // The popup receives pointer events before the widgets below it.
+    e.hover([20., 12.], &mut log).click(&mut log);
+    e.hover([26., 6.], &mut log).click(&mut log);
+    assert_eq!(log, vec!["popup", "sibling"]);
+
+    // Pressing the opener again hides the popup.
+    e.hover([6., 6.], &mut log).click(&mut log);
+    e.layout([32., 32.], &log);
+    assert!(e.root.is_empty());
+    assert_eq!(pixel(&mut e, 20, 12), Rgba8::GREEN);
+
+    // Modal popups are moved to fit in the overlay, and block input below them.
+    log.clear();
+    let mut e = Environment::new(widget(true));
+    e.layout([32., 32.], &log);
+    e.hover([6., 6.], &mut log).click(&mut log);
+    e.layout([32., 32.], &log);
+    assert_eq!(pixel(&mut e, 31, 31), Rgba8::BLUE);
+    assert_eq!(pixel(&mut e, 15, 15), Rgba8::GREEN);
+
+    e.hover([26., 6.], &mut log).click(&mut log);
+    e.hover([6., 6.], &mut log).click(&mut log);
+    e.hover([20., 20.], &mut log).click(&mut log);
+    assert_eq!(log, vec!["popup"]);
+    assert_eq!(e.root.len(), 1);
+}
+
+#[test]
+fn test_overlay_hide() {
+    /// Toggles a menu below itself when pressed.
+    struct Opener(Option<OverlayId>);
+
+    impl Widget<u32> for Opener {
+        fn layout(
+            &mut self,
+            constraints: BoxConstraints,
+            _ctx: &LayoutCtx<'_>,
+            _data: &u32,
+            _env: &Env,
+        ) -> Size {
+            constraints.constrain([8., 8.])
+        }
+
+        fn paint(&mut self, _canvas: Canvas<'_>, _data: &u32) {}
+
+        fn event(
+            &mut self,
+            event: &WidgetEvent,
+            ctx: &Context<'_>,
+            _data: &mut u32,
+        ) -> ControlFlow<()> {
+            if let WidgetEvent::MouseDown(_) = event {
+                match self.0.take() {
+                    Some(id) => ctx.hide_overlay(id),
+                    None => self.0 = Some(ctx.show_overlay(Menu, [0., 8.])),
+                }
+            }
+            ControlFlow::Continue(())
+        }
+    }
+
+    /// Shows a submenu beside itself when pressed, and counts how often it lost focus.
+    struct Menu;
+
+    impl Widget<u32> for Menu {
+        fn layout(
+            &mut self,
+            constraints: BoxConstraints,
+            _ctx: &LayoutCtx<'_>,
+            _data: &u32,
+            _env: &Env,
+        ) -> Size {
+            constraints.constrain([8., 8.])
+        }
+
+        fn paint(&mut self, _canvas: Canvas<'_>, _data: &u32) {}
+
+        fn event(
+            &mut self,
+            event: &WidgetEvent,
+            ctx: &Context<'_>,
+            data: &mut u32,
+        ) -> ControlFlow<()> {
+            match event {
+                WidgetEvent::MouseDown(_) => {
+                    ctx.show_overlay::<u32>(Rgba8::WHITE.sized([4., 4.]), [8., 0.]);
+                }
+                WidgetEvent::Focus(false) => *data += 1,
+                _ => {}
+            }
+            ControlFlow::Continue(())
+        }
+
+        fn accepts_focus(&self) -> bool {
+            true
+        }
+    }
+
+    let mut blurs = 0;
+    let mut e = Environment::new(Overlay::new(align(Opener(None)).top(0.).left(0.)));
+    e.layout([32., 32.], &blurs);
+
+    e.hover([4., 4.], &mut blurs).click(&mut blurs);
+    e.hover([4., 12.], &mut blurs).click(&mut blurs);
+    assert_eq!(e.root.len(), 2);
+    assert!(e.focused().is_some());
+
+    // Hiding the menu hides its submenu, and it's told it lost focus.
+    e.hover([4., 4.], &mut blurs).click(&mut blurs);
+    e.tick(Duration::ZERO, &mut blurs);
+    assert!(e.root.is_empty());
+    assert_eq!(e.focused(), None);
+    assert_eq!(blurs, 1);
+}
+
+#[test]
+fn test_overlay_show() {
+    /// Shows a popup under the cursor when pressed.
+    struct Opener;
+
+    impl Widget<u32> for Opener {
+        fn paint(&mut self, _canvas: Canvas<'_>, _data: &u32) {}
+
+        fn event(
+            &mut self,
+            event: &WidgetEvent,
+            ctx: &Context<'_>,
+            _data: &mut u32,
+        ) -> ControlFlow<()> {
+            if let WidgetEvent::MouseDown(_) = event {
+                ctx.show_overlay(Early(false), [0., 0.]);
+            }
+            ControlFlow::Continue(())
+        }
+    }
+
+    /// Counts the events it receives before it's initialized.
+    struct Early(bool);
+
+    impl Widget<u32> for Early {
+        fn paint(&mut self, _canvas: Canvas<'_>, _data: &u32) {}
+
+        fn event(
+            &mut self,
+            _event: &WidgetEvent,
+            _ctx: &Context<'_>,
+            data: &mut u32,
+        ) -> ControlFlow<()> {
+            if !self.0 {
+                *data += 1;
+            }
+            ControlFlow::Continue(())
+        }
+
+        fn lifecycle(
+            &mut self,
+            _lifecycle: &WidgetLifecycle<'_>,
+            _ctx: &Context<'_>,
+            _data: &u32,
+            _env: &Env,
+        ) {
+            self.0 = true;
+        }
+    }
+
+    let mut ui = Ui::new(Opener, 0).size(LogicalSize::new(16., 16.));
+    ui.update(Duration::ZERO);
+
+    // Layers shown while handling events only receive events after their first update.
+    ui.handle_event(WindowEvent::CursorMoved {
+        position: LogicalPosition::new(4., 4.),
+    });
+    ui.handle_event(WindowEvent::MouseInput {
+        state: InputState::Pressed,
+        button: MouseButton::Left,
+        modifiers: Default::default(),
+    });
+    ui.handle_event(WindowEvent::Focused(true));
+    ui.update(Duration::ZERO);
+    ui.update(Duration::ZERO);
+
+    assert_eq!(*ui.data(), 0);
+}
+
+#[test]
+fn test_overlay_lift() {
+    /// Shows a popup below itself when pressed, which calls a function when clicked.
+    struct Popper<U>(fn(&mut U));
+
+    impl<U: 'static> Widget<U> for Popper<U> {
+        fn layout(
+            &mut self,
+            constraints: BoxConstraints,
+            _ctx: &LayoutCtx<'_>,
+            _data: &U,
+            _env: &Env,
+        ) -> Size {
+            constraints.constrain([8., 8.])
+        }
+
+        fn paint(&mut self, mut canvas: Canvas<'_>, _data: &U) {
+            canvas.fill(Rect::new([0., 0.], [8., 8.]), Rgba8::RED);
+        }
+
+        fn event(
+            &mut self,
+            event: &WidgetEvent,
+            ctx: &Context<'_>,
+            _data: &mut U,
+        ) -> ControlFlow<()> {
+            if let WidgetEvent::MouseDown(_) = event {
+                let f = self.0;
+                let popup = Rgba8::BLUE
+                    .sized([8., 8.])
+                    .on_click(move |_, data: &mut U| f(data));
+
+                ctx.show_overlay(popup, [0., 8.]);
+
+                return ControlFlow::Break(());
+            }
+            ControlFlow::Continue(())
+        }
+    }
+
+    struct Counts {
+        items: Vec<(u64, u32)>,
+        total: u32,
+    }
+
+    // Popups shown by list children and widgets behind a lens receive the same data
+    // as the widget that showed them.
+    let list = List::new(
+        |c: &Counts| &c.items,
+        |c: &mut Counts| &mut c.items,
+        |item| item.0,
+        |_| Popper(|item: &mut (u64, u32)| item.1 += 1),
+    )
+    .horizontal();
+    let total = Popper(|n: &mut u32| *n += 1).lens(lens::Field::new(
+        |c: &Counts| &c.total,
+        |c: &mut Counts| &mut c.total,
+    ));
+    let mut data = Counts {
+        items: vec![(1, 0), (2, 0)],
+        total: 0,
+    };
+    let mut e = Environment::new(Overlay::new(align(hstack((list, total))).top(0.).left(0.)));
+    e.layout([32., 32.], &data);
+
+    e.hover([12., 4.], &mut data).click(&mut data);
+    e.layout([32., 32.], &data);
+    e.hover([12., 12.], &mut data).click(&mut data);
+    assert_eq!(data.items, [(1, 0), (2, 1)]);
+
+    e.hover([20., 4.], &mut data).click(&mut data);
+    e.layout([32., 32.], &data);
+    e.hover([20., 12.], &mut data).click(&mut data);
+    assert_eq!(data.total, 1);
+    assert_eq!(e.root.len(), 2);
+
+    // Popups of dropped list children are hidden.
+    data.items.pop();
+    e.update(&data);
+    assert_eq!(e.root.len(), 1);
+
+    // Including children dropped because of duplicate keys.
+    data.items.push((1, 0));
+    e.layout([32., 32.], &data);
+    e.hover([4., 4.], &mut data).click(&mut data);
+    assert_eq!(e.root.len(), 2);
+
+    data.items.pop();
+    e.update(&data);
+    assert_eq!(e.root.len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "but the overlay layer has u32")]
+fn test_overlay_data_mismatch() {
+    /// Shows a popup of the wrong data when pressed.
+    struct Wrong;
+
+    impl Widget<u32> for Wrong {
+        fn paint(&mut self, _canvas: Canvas<'_>, _data: &u32) {}
+
+        fn event(
+            &mut self,
+            event: &WidgetEvent,
+            ctx: &Context<'_>,
+            _data: &mut u32,
+        ) -> ControlFlow<()> {
+            if let WidgetEvent::MouseDown(_) = event {
+                ctx.show_overlay::<String>(Rgba8::BLUE.sized([8., 8.]), [0., 0.]);
+            }
+            ControlFlow::Continue(())
+        }
+    }
+
+    let mut data = 0;
+    let mut e = Environment::new(Overlay::new(Wrong));
+    e.layout([8., 8.], &data)
+        .hover([4., 4.], &mut data)
+        .click(&mut data);
+}
+
+#[test]
+fn test_list() {
+    #[derive(Debug, PartialEq, Eq)]
+    struct Item {
+        id: u64,
+        clicks: u32,
+    }
+
+    /// Paints itself green once initialized, and red otherwise. Requests a timer when
+    /// initialized, and counts the timers fired.
+    struct Row {
+        initialized: bool,
+        timers: Rc<Cell<u32>>,
+    }
+
+    impl Widget<Item> for Row {
+        fn layout(
+            &mut self,
+            constraints: BoxConstraints,
+            _ctx: &LayoutCtx<'_>,
+            _data: &Item,
+            _env: &Env,
+        ) -> Size {
+            constraints.constrain([16., 8.])
+        }
+
+        fn paint(&mut self, mut canvas: Canvas<'_>, _data: &Item) {
+            let color = if self.initialized {
+                Rgba8::GREEN
+            } else {
+                Rgba8::RED
+            };
+            canvas.fill(Rect::new([0., 0.], [16., 8.]), color);
+        }
+
+        fn event(
+            &mut self,
+            event: &WidgetEvent,
+            _ctx: &Context<'_>,
+            _data: &mut Item,
+        ) -> ControlFlow<()> {
+            if let WidgetEvent::Timer(_) = event {
+                self.timers.set(self.timers.get() + 1);
+            }
+            ControlFlow::Continue(())
+        }
+
+        fn lifecycle(
+            &mut self,
+            _lifecycle: &WidgetLifecycle<'_>,
+            ctx: &Context<'_>,
+            _data: &Item,
+            _env: &Env,
+        ) {
+            self.initialized = true;
+            ctx.request_timer(Duration::from_millis(10));
+        }
+    }
+
+    let item = |id| Item { id, clicks: 0 };
+    let builds = Rc::new(Cell::new(0));
+    let timers = Rc::new(Cell::new(0));
+    let list = List::with_lens(lens::Identity, |item| item.id, {
+        let builds = builds.clone();
+        let timers = timers.clone();
+        move |_| {
+            builds.set(builds.get() + 1);
+            Row {
+                initialized: false,
+                timers: timers.clone(),
+            }
+            .on_click(|_, item: &mut Item| item.clicks += 1)
+        }
+    });
+    let mut items = vec![item(1), item(2)];
+    let mut e = Environment::new(align(list).top(0.).left(0.));
+    e.update(&items).layout([32., 32.], &items);
+
+    let pixel = |e: &mut Environment<_, _>, items: &Vec<Item>, x, y| {
+        *e.capture(items).unwrap().sample([x, y].into()).unwrap()
+    };
+    assert_eq!(builds.get(), 2);
+    assert_eq!(pixel(&mut e, &items, 0, 0), Rgba8::GREEN);
+    assert_eq!(pixel(&mut e, &items, 15, 15), Rgba8::GREEN);
+    assert_eq!(pixel(&mut e, &items, 0, 16), Rgba8::TRANSPARENT);
+
+    // Each child receives its item.
+    e.hover([4., 12.], &mut items).click(&mut items);
+    assert_eq!(items[1].clicks, 1);
+
+    // Children are kept for items that remain, and built for new items, which are
+    // initialized.
+    items.remove(0);
+    items.insert(0, item(3));
+    items.push(item(4));
+    e.update(&items).layout([32., 32.], &items);
+
+    assert_eq!(builds.get(), 4);
+    assert_eq!(pixel(&mut e, &items, 0, 0), Rgba8::GREEN);
+    assert_eq!(pixel(&mut e, &items, 0, 16), Rgba8::GREEN);
+
+    // Requests made while initializing are honoured.
+    e.tick(Duration::from_millis(10), &mut items);
+    assert_eq!(timers.get(), 3);
+
+    e.hover([4., 12.], &mut items).click(&mut items);
+    e.hover([4., 20.], &mut items).click(&mut items);
+    assert_eq!(
+        items,
+        vec![
+            item(3),
+            Item { id: 2, clicks: 2 },
+            Item { id: 4, clicks: 1 }
+        ]
+    );
+
+    // Unchanged items don't cause rebuilds.
+    e.update(&items).layout([32., 32.], &items);
+    assert_eq!(builds.get(), 4);
+}
+
+#[test]
+fn test_lens() {
+    // A widget that only knows about `Data`.
+    fn counter() -> impl Widget<Data> {
+        painter(|mut canvas, data: &Data| {
+            let color = if data.clicks > 0 {
+                Rgba8::GREEN
+            } else {
+                Rgba8::RED
+            };
+            canvas.fill(Rect::new([0., 0.], [16., 16.]), color);
+        })
+        .sized([16., 16.])
+        .on_click(|_, data: &mut Data| data.clicks += 1)
+        .on_hover(|hot, _, data: &mut Data| data.hot = hot)
+    }
+    let first = lens::Field::new(|d: &(Data, Data)| &d.0, |d: &mut (Data, Data)| &mut d.0);
+    let second = lens::Field::new(|d: &(Data, Data)| &d.1, |d: &mut (Data, Data)| &mut d.1);
+
+    let mut data = (Data::default(), Data::default());
+    let mut e = Environment::new(
+        align(hstack((counter().lens(first), counter().lens(second))))
+            .top(0.)
+            .left(0.),
+    );
+    e.layout([32., 16.], &data);
+
+    e.hover([20., 4.], &mut data).click(&mut data);
+    assert_eq!(data.0, Data::default());
+    assert_eq!(
+        data.1,
+        Data {
+            clicks: 1,
+            hot: true
+        }
+    );
+
+    let image = e.capture(&data).unwrap();
+    assert_eq!(image.sample([4, 4].into()), Some(&Rgba8::RED));
+    assert_eq!(image.sample([20, 4].into()), Some(&Rgba8::GREEN));
+}
+
+#[test]
+fn test_either() {
+    let mut data = Data::default();
+    let mut e = Environment::new(Either::new(
+        |data: &Data| data.clicks % 2 == 0,
+        Rgba8::GREEN.on_click(|_, data: &mut Data| data.clicks += 1),
+        Rgba8::BLUE.on_click(|_, data: &mut Data| data.clicks += 1),
+    ));
+    let pixel = |e: &mut Environment<_, _>, data: &Data| {
+        *e.update(data)
+            .layout([8., 8.], data)
+            .capture(data)
+            .unwrap()
+            .sample([4, 4].into())
+            .unwrap()
+    };
+    assert_eq!(pixel(&mut e, &data), Rgba8::GREEN);
+
+    // Only the child shown receives events.
+    e.hover([4., 4.], &mut data).click(&mut data);
+    assert_eq!(data.clicks, 1);
+    assert_eq!(pixel(&mut e, &data), Rgba8::BLUE);
+
+    e.click(&mut data);
+    assert_eq!(data.clicks, 2);
+    assert_eq!(pixel(&mut e, &data), Rgba8::GREEN);
+}
+
+#[test]
+fn test_either_hidden() {
+    /// Logs updates, hover changes and loss of focus.
+    struct Branch(&'static str, Rc<RefCell<Vec<String>>>);
+
+    impl Widget<bool> for Branch {
+        fn paint(&mut self, _canvas: Canvas<'_>, _data: &bool) {}
+
+        fn update(&mut self, _ctx: &Context<'_>, data: &bool) {
+            self.1
+                .borrow_mut()
+                .push(format!("{} update {data}", self.0));
+        }
+
+        fn event(
+            &mut self,
+            event: &WidgetEvent,
+            _ctx: &Context<'_>,
+            _data: &mut bool,
+        ) -> ControlFlow<()> {
+            let name = match event {
+                WidgetEvent::MouseEnter => "enter",
+                WidgetEvent::MouseExit => "exit",
+                WidgetEvent::Focus(false) => "blur",
+                _ => return ControlFlow::Continue(()),
+            };
+            self.1.borrow_mut().push(format!("{} {name}", self.0));
+
+            ControlFlow::Continue(())
+        }
+
+        fn accepts_focus(&self) -> bool {
+            true
+        }
+    }
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut data = true;
+    let mut e = Environment::new(Either::new(
+        |data: &bool| *data,
+        Branch("first", log.clone()),
+        Branch("second", log.clone()),
+    ));
+    e.layout([8., 8.], &data)
+        .hover([4., 4.], &mut data)
+        .click(&mut data);
+    assert!(e.focused().is_some());
+    log.borrow_mut().clear();
+
+    // Both children are updated.
+    data = false;
+    e.update(&data).layout([8., 8.], &data);
+    assert_eq!(*log.borrow(), ["second update false", "first update false"]);
+    log.borrow_mut().clear();
+
+    // The hidden child loses focus, but its hover state only changes on the next
+    // pointer event.
+    e.event(&WidgetEvent::Tick(Duration::ZERO), &mut data);
+    assert_eq!(e.focused(), None);
+    assert_eq!(*log.borrow(), ["first blur"]);
+    log.borrow_mut().clear();
+
+    e.hover([5., 5.], &mut data);
+    assert_eq!(*log.borrow(), ["first exit", "second enter"]);
+}
+
+#[test]
+fn test_view_switcher() {
+    /// Paints its color once initialized, and red otherwise.
+    struct Screen {
+        color: Rgba8,
+        initialized: bool,
+    }
+
+    impl Widget<u32> for Screen {
+        fn paint(&mut self, mut canvas: Canvas<'_>, _data: &u32) {
+            let color = if self.initialized {
+                self.color
+            } else {
+                Rgba8::RED
+            };
+            canvas.fill(Rect::new([0., 0.], [8., 8.]), color);
+        }
+
+        fn lifecycle(
+            &mut self,
+            _lifecycle: &WidgetLifecycle<'_>,
+            _ctx: &Context<'_>,
+            _data: &u32,
+            _env: &Env,
+        ) {
+            self.initialized = true;
+        }
+    }
+
+    let builds = Rc::new(Cell::new(0));
+    let switcher = ViewSwitcher::new(|mode: &u32| *mode / 10, {
+        let builds = builds.clone();
+        move |key, _| {
+            builds.set(builds.get() + 1);
+
+            let color = if *key == 0 { Rgba8::GREEN } else { Rgba8::BLUE };
+            Screen {
+                color,
+                initialized: false,
+            }
+            .on_click(|_, mode: &mut u32| *mode += 10)
+            .boxed()
+        }
+    });
+    let mut mode = 0;
+    let mut e = Environment::new(switcher);
+    let pixel = |e: &mut Environment<_, _>, mode: &u32| {
+        *e.update(mode)
+            .layout([8., 8.], mode)
+            .capture(mode)
+            .unwrap()
+            .sample([4, 4].into())
+            .unwrap()
+    };
+    assert_eq!(pixel(&mut e, &mode), Rgba8::GREEN);
+    assert_eq!(builds.get(), 1);
+
+    // The child isn't rebuilt while the key stays the same.
+    mode = 5;
+    assert_eq!(pixel(&mut e, &mode), Rgba8::GREEN);
+    assert_eq!(builds.get(), 1);
+
+    // The child is rebuilt when the key changes, and initialized.
+    e.hover([4., 4.], &mut mode).click(&mut mode);
+    assert_eq!(mode, 15);
+    assert_eq!(pixel(&mut e, &mode), Rgba8::BLUE);
+    assert_eq!(builds.get(), 2);
+    assert_eq!(e.root.current(), Some(&1));
+
+    // The new child receives events, without the cursor having moved.
+    e.click(&mut mode);
+    assert_eq!(mode, 25);
+    assert_eq!(pixel(&mut e, &mode), Rgba8::BLUE);
+    assert_eq!(builds.get(), 3);
+}
+
+#[test]
+fn test_view_switcher_replaced() {
+    /// Shows a popup and switches to the next page when clicked, and counts how
+    /// often it lost focus.
+    struct Page;
+
+    impl Widget<(u32, u32)> for Page {
+        fn paint(&mut self, _canvas: Canvas<'_>, _data: &(u32, u32)) {}
+
+        fn event(
+            &mut self,
+            event: &WidgetEvent,
+            ctx: &Context<'_>,
+            data: &mut (u32, u32),
+        ) -> ControlFlow<()> {
+            match event {
+                WidgetEvent::MouseDown(_) => {
+                    ctx.show_overlay::<(u32, u32)>(Rgba8::WHITE.sized([2., 2.]), [0., 0.]);
+                    data.0 += 1;
+                }
+                WidgetEvent::Focus(false) => data.1 += 1,
+                _ => {}
+            }
+            ControlFlow::Continue(())
+        }
+
+        fn accepts_focus(&self) -> bool {
+            true
+        }
+    }
+
+    let mut data = (0, 0);
+    let mut e = Environment::new(Overlay::new(ViewSwitcher::new(
+        |data: &(u32, u32)| data.0,
+        |_, _| Page.boxed(),
+    )));
+    e.layout([8., 8.], &data)
+        .hover([4., 4.], &mut data)
+        .click(&mut data);
+
+    // The page is replaced as soon as the popup is laid out. The replaced page's
+    // popup is hidden, and it's told it lost focus.
+    e.tick(Duration::ZERO, &mut data);
+    assert_eq!(e.root.len(), 0);
+    assert_eq!(e.focused(), None);
+    assert_eq!(data, (1, 1));
+}
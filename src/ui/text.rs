@@ -3,7 +3,7 @@ pub mod font;
 use crate::gfx::*;
 use crate::math::*;
 
-use super::{Canvas, Env, IntoPaint, LayoutCtx, Paint, Widget};
+use super::{BoxConstraints, Canvas, Env, IntoPaint, LayoutCtx, Paint, Widget};
 pub use font::{Error as FontError, Font, FontFormat, FontId};
 
 pub struct Text {
@@ -84,11 +84,17 @@ impl IntoPaint for Text {
 }
 
 impl<T> Widget<T> for Text {
-    fn layout(&mut self, _parent: Size, ctx: &LayoutCtx<'_>, _data: &T, _env: &Env) -> Size {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        _data: &T,
+        _env: &Env,
+    ) -> Size {
         if let Some(font) = ctx.fonts.get(&self.font) {
             self.size = Size::new(font.text_width(&self.body), font.text_height());
         }
-        self.size
+        constraints.constrain(self.size)
     }
 
     fn paint(&mut self, mut canvas: Canvas<'_>, _data: &T) {
@@ -1,13 +1,35 @@
 pub mod align;
 pub use align::Align;
+pub mod flex;
+pub use flex::{CrossAxisAlignment, Flex, MainAxisAlignment};
+pub mod container;
+pub use container::Container;
+pub mod either;
+pub use either::Either;
+pub mod grid;
+pub use grid::{CellAlignment, Grid, GridItem, TrackSize};
 pub mod hstack;
 pub use hstack::HStack;
+pub mod vstack;
+pub use vstack::VStack;
+pub mod overlay;
+pub use overlay::Overlay;
+pub mod lens_wrap;
+pub use lens_wrap::LensWrap;
+pub mod list;
+pub use list::List;
+pub mod padded;
+pub use padded::Padded;
 pub mod pod;
 pub use pod::Pod;
 pub mod image;
 pub use image::Image;
+pub mod scroll;
+pub use scroll::Scroll;
 pub mod sized_box;
 pub use sized_box::SizedBox;
+pub mod view_switcher;
+pub use view_switcher::ViewSwitcher;
 pub mod zstack;
 pub use zstack::ZStack;
 pub mod painter;
@@ -17,8 +39,8 @@ pub use controller::Controller;
 pub mod button;
 pub use button::Button;
 pub mod click;
-pub use click::Click;
+pub use click::{Click, ClickCounter, ClickEvent, Clicks, LongPress};
 pub mod hover;
 pub use hover::Hover;
 pub mod widget;
-pub use widget::{Widget, WidgetEvent, WidgetExt, WidgetId, WidgetTuple};
+pub use widget::{Drag, MouseEvent, Widget, WidgetEvent, WidgetExt, WidgetId, WidgetTuple};
@@ -43,8 +43,29 @@ impl<T> Align<T> {
 }
 
 impl<T> Widget<T> for Align<T> {
-    fn layout(&mut self, parent: Size, ctx: &LayoutCtx<'_>, data: &T, env: &Env) -> Size {
-        self.size = self.widget.layout(parent, ctx, data, env);
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        self.size = self.widget.layout(constraints.loosen(), ctx, data, env);
+
+        // Take up all the space allowed, except in unbounded dimensions,
+        // where we wrap the child.
+        let parent = constraints.constrain([
+            if constraints.max.w.is_finite() {
+                constraints.max.w
+            } else {
+                self.size.w
+            },
+            if constraints.max.h.is_finite() {
+                constraints.max.h
+            } else {
+                self.size.h
+            },
+        ]);
 
         let mut x = (parent.w - self.widget.size.w) / 2.;
         let mut y = (parent.h - self.widget.size.h) / 2.;
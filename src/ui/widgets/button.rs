@@ -22,8 +22,14 @@ impl<T> Button<T> {
 }
 
 impl<T> Widget<T> for Button<T> {
-    fn layout(&mut self, parent: Size, ctx: &LayoutCtx<'_>, data: &T, env: &Env) -> Size {
-        self.child.layout(parent, ctx, data, env)
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        self.child.layout(constraints, ctx, data, env)
     }
 
     fn paint(&mut self, canvas: Canvas<'_>, data: &T) {
@@ -1,7 +1,16 @@
+use std::time::Duration;
+
 use crate::platform::MouseButton;
 use crate::ui::widgets::Controller;
 use crate::ui::*;
 
+/// Maximum time between two clicks for them to count as a multi-click.
+pub const CLICK_INTERVAL: Duration = Duration::from_millis(500);
+/// Maximum distance between two clicks for them to count as a multi-click.
+pub const CLICK_DISTANCE: f32 = 4.;
+/// Time a button has to be held for a long press.
+pub const LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+
 /// Adds click behavior to a widget.
 pub struct Click<T> {
     /// A closure that will be invoked when the child widget is clicked.
@@ -25,15 +34,257 @@ impl<T, W: Widget<T>> Controller<T, W> for Click<T> {
         ctx: &Context<'_>,
         data: &mut T,
     ) -> ControlFlow<()> {
+        // Let the child handle the event first, so that clicks on nested
+        // clickable widgets don't also trigger this one.
+        child.event(event, ctx, data)?;
+
         match event {
-            WidgetEvent::MouseDown(MouseButton::Left) => {
-                return ControlFlow::Break(());
-            }
-            WidgetEvent::MouseUp(MouseButton::Left) => {
+            WidgetEvent::MouseDown(MouseEvent {
+                button: MouseButton::Left,
+                ..
+            }) => ControlFlow::Break(()),
+            WidgetEvent::MouseUp(MouseEvent {
+                button: MouseButton::Left,
+                ..
+            }) => {
                 if ctx.active && ctx.hot {
                     (self.action)(ctx, data);
                 }
-                return ControlFlow::Break(());
+                ControlFlow::Break(())
+            }
+            _ => ControlFlow::Continue(()),
+        }
+    }
+}
+
+/// A click, along with the number of clicks in quick succession.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ClickEvent {
+    /// Button clicked.
+    pub button: MouseButton,
+    /// Number of clicks, eg. `2` for a double-click.
+    pub count: u32,
+    /// Position of the click, in widget coordinates.
+    pub position: Point,
+}
+
+/// Counts consecutive clicks. Clicks are consecutive if they are made with the same
+/// button, within a certain interval and distance of each other.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use rgx::math::Point;
+/// use rgx::platform::MouseButton;
+/// use rgx::ui::widgets::click::ClickCounter;
+///
+/// let mut counter = ClickCounter::default();
+///
+/// assert_eq!(counter.click(MouseButton::Left, Point::new(1., 1.)), 1);
+/// counter.tick(Duration::from_millis(100));
+/// assert_eq!(counter.click(MouseButton::Left, Point::new(2., 1.)), 2);
+/// counter.tick(Duration::from_millis(600));
+/// assert_eq!(counter.click(MouseButton::Left, Point::new(2., 1.)), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClickCounter {
+    interval: Duration,
+    distance: f32,
+    /// Time elapsed, as the sum of ticks.
+    time: Duration,
+    /// Last click, with its time.
+    last: Option<(MouseButton, Point, Duration)>,
+    count: u32,
+}
+
+impl Default for ClickCounter {
+    fn default() -> Self {
+        Self {
+            interval: CLICK_INTERVAL,
+            distance: CLICK_DISTANCE,
+            time: Duration::ZERO,
+            last: None,
+            count: 0,
+        }
+    }
+}
+
+impl ClickCounter {
+    /// Set the maximum time between consecutive clicks.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set the maximum distance between consecutive clicks.
+    pub fn distance(mut self, distance: f32) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    /// Advance time.
+    pub fn tick(&mut self, delta: Duration) {
+        self.time += delta;
+    }
+
+    /// Register a click, and return the number of consecutive clicks so far.
+    pub fn click(&mut self, button: MouseButton, position: Point) -> u32 {
+        let consecutive = match self.last {
+            Some((b, p, time)) => {
+                b == button
+                    && self.time - time <= self.interval
+                    && (position - p).magnitude() <= self.distance
+            }
+            None => false,
+        };
+        self.count = if consecutive { self.count + 1 } else { 1 };
+        self.last = Some((button, position, self.time));
+        self.count
+    }
+}
+
+/// Adds multi-click behavior to a widget. The action is invoked on every click,
+/// with the number of consecutive clicks, as counted by the UI in [`MouseEvent::count`].
+pub struct Clicks<T> {
+    action: Box<dyn Fn(&ClickEvent, &Context<'_>, &mut T)>,
+    /// Click count of the button currently held.
+    pressed: Option<u32>,
+}
+
+impl<T> Clicks<T> {
+    /// Create a new multi-click [`Controller`] widget.
+    pub fn new(action: impl Fn(&ClickEvent, &Context<'_>, &mut T) + 'static) -> Self {
+        Self {
+            action: Box::new(action),
+            pressed: None,
+        }
+    }
+}
+
+impl<T, W: Widget<T>> Controller<T, W> for Clicks<T> {
+    fn event(
+        &mut self,
+        child: &mut W,
+        event: &WidgetEvent,
+        ctx: &Context<'_>,
+        data: &mut T,
+    ) -> ControlFlow<()> {
+        child.event(event, ctx, data)?;
+
+        match event {
+            WidgetEvent::MouseDown(mouse) => {
+                self.pressed = Some(mouse.count);
+
+                ControlFlow::Break(())
+            }
+            WidgetEvent::MouseUp(mouse) => {
+                if let Some(count) = self.pressed.take() {
+                    if ctx.active && ctx.hot {
+                        let click = ClickEvent {
+                            button: mouse.button,
+                            count,
+                            position: mouse.position,
+                        };
+                        (self.action)(&click, ctx, data);
+                    }
+                }
+                ControlFlow::Break(())
+            }
+            _ => ControlFlow::Continue(()),
+        }
+    }
+}
+
+/// Adds long-press behavior to a widget. The action is invoked once the left button
+/// has been held down for long enough, without moving.
+///
+/// When a long press is detected, the `MouseUp` event that follows doesn't propagate,
+/// so that the press isn't also handled as a click.
+pub struct LongPress<T> {
+    action: Box<dyn Fn(&Context<'_>, &mut T)>,
+    duration: Duration,
+    distance: f32,
+    /// Start of the press in progress, and time held.
+    press: Option<(Point, Duration)>,
+    /// Whether the action was invoked for the current press.
+    fired: bool,
+}
+
+impl<T> LongPress<T> {
+    /// Create a new long-press [`Controller`] widget.
+    pub fn new(action: impl Fn(&Context<'_>, &mut T) + 'static) -> Self {
+        Self {
+            action: Box::new(action),
+            duration: LONG_PRESS_DURATION,
+            distance: CLICK_DISTANCE,
+            press: None,
+            fired: false,
+        }
+    }
+
+    /// Set the time the button has to be held for.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Set the distance the pointer can move before the press is cancelled.
+    pub fn distance(mut self, distance: f32) -> Self {
+        self.distance = distance;
+        self
+    }
+}
+
+impl<T, W: Widget<T>> Controller<T, W> for LongPress<T> {
+    fn event(
+        &mut self,
+        child: &mut W,
+        event: &WidgetEvent,
+        ctx: &Context<'_>,
+        data: &mut T,
+    ) -> ControlFlow<()> {
+        match event {
+            WidgetEvent::Tick(delta) => {
+                if let Some((_, held)) = &mut self.press {
+                    *held += *delta;
+
+                    if *held >= self.duration {
+                        self.press = None;
+                        self.fired = true;
+
+                        (self.action)(ctx, data);
+                    } else {
+                        // Make sure we keep receiving ticks while the button is held.
+                        ctx.request_paint();
+                    }
+                }
+            }
+            WidgetEvent::MouseDown(MouseEvent {
+                button: MouseButton::Left,
+                position,
+                ..
+            }) => {
+                self.press = Some((*position, Duration::ZERO));
+                self.fired = false;
+
+                ctx.request_paint();
+            }
+            WidgetEvent::MouseMove(point) => {
+                if let Some((start, _)) = self.press {
+                    if (*point - start).magnitude() > self.distance {
+                        self.press = None;
+                    }
+                }
+            }
+            WidgetEvent::MouseUp(MouseEvent {
+                button: MouseButton::Left,
+                ..
+            }) => {
+                self.press = None;
+
+                if std::mem::take(&mut self.fired) {
+                    return ControlFlow::Break(());
+                }
             }
             _ => {}
         }
@@ -0,0 +1,137 @@
+use std::ops::ControlFlow;
+
+use crate::gfx::{Fill, Rectangle, Stroke};
+use crate::ui::widgets::Padded;
+use crate::ui::*;
+
+/// A container that decorates its child with padding, a background, a border,
+/// and rounded corners.
+///
+/// The border doesn't take up any space: by default it's drawn around the container,
+/// and with [`Stroke::inside`], it's drawn over the padding.
+///
+/// ```
+/// use rgx::gfx::{Rgba8, Stroke};
+/// use rgx::ui::widgets::Container;
+/// use rgx::ui::WidgetExt;
+///
+/// let card = Container::<()>::new(Rgba8::RED.sized([32., 32.]))
+///     .padding(8.)
+///     .background(Rgba8::WHITE)
+///     .border(Stroke::new(1., Rgba8::BLACK).inside())
+///     .radius(4.);
+/// ```
+pub struct Container<T> {
+    widget: Padded<T>,
+    background: Fill,
+    border: Stroke,
+    radius: f32,
+    size: Size,
+}
+
+impl<T> Container<T> {
+    pub fn new(widget: impl Widget<T> + 'static) -> Self {
+        Self {
+            widget: Padded::new(widget, Padding::default()),
+            background: Fill::Empty,
+            border: Stroke::NONE,
+            radius: 0.,
+            size: Size::ZERO,
+        }
+    }
+
+    /// Set the padding between the border and the child.
+    pub fn padding(self, padding: impl Into<Padding>) -> Self {
+        Self {
+            widget: self.widget.padding(padding),
+            ..self
+        }
+    }
+
+    /// Set the background.
+    pub fn background(self, background: impl Into<Fill>) -> Self {
+        Self {
+            background: background.into(),
+            ..self
+        }
+    }
+
+    /// Set the border.
+    pub fn border(self, border: Stroke) -> Self {
+        Self { border, ..self }
+    }
+
+    /// Set the radius of the corners.
+    pub fn radius(self, radius: f32) -> Self {
+        Self { radius, ..self }
+    }
+
+    fn rectangle(&self) -> Rectangle {
+        Rectangle::new(Point::ORIGIN, self.size).radius(self.radius)
+    }
+}
+
+impl<T> Widget<T> for Container<T> {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        self.size = self.widget.layout(constraints, ctx, data, env);
+        self.size
+    }
+
+    fn paint(&mut self, mut canvas: Canvas<'_>, data: &T) {
+        if matches!(self.background, Fill::Solid(_)) {
+            canvas.paint(self.rectangle().fill(self.background));
+        }
+        self.widget.paint(canvas.clone(), data);
+
+        // The border is painted last, so that it isn't covered by the child.
+        if self.border != Stroke::NONE {
+            // Strokes are drawn inward from their offset, so shift the border out by its
+            // width, to draw it outside of the container unless it's an inside stroke.
+            let border = Stroke {
+                offset: self.border.offset + self.border.width,
+                ..self.border
+            };
+            canvas.paint(self.rectangle().with_stroke(border));
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<'_>, data: &T) {
+        self.widget.update(ctx, data);
+    }
+
+    fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        self.widget.event(event, ctx, data)
+    }
+
+    fn lifecycle(
+        &mut self,
+        lifecycle: &WidgetLifecycle<'_>,
+        ctx: &Context<'_>,
+        data: &T,
+        env: &Env,
+    ) {
+        self.widget.lifecycle(lifecycle, ctx, data, env)
+    }
+
+    fn frame(&mut self, surfaces: &Surfaces, data: &mut T) {
+        self.widget.frame(surfaces, data);
+    }
+
+    fn cursor(&self) -> Option<&'static str> {
+        self.widget.cursor()
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.widget.contains(point)
+    }
+
+    fn display(&self) -> String {
+        format!("Container({})", self.widget.display())
+    }
+}
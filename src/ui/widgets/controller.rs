@@ -2,6 +2,17 @@ use crate::ui::*;
 
 /// A type of widget that implements a "control".
 pub trait Controller<T, W: Widget<T>> {
+    /// Analogous to [`Widget::capture`].
+    fn capture(
+        &mut self,
+        child: &mut W,
+        event: &WidgetEvent,
+        ctx: &Context<'_>,
+        data: &mut T,
+    ) -> ControlFlow<()> {
+        child.capture(event, ctx, data)
+    }
+
     /// Analogous to [`Widget::event`].
     fn event(
         &mut self,
@@ -49,8 +60,14 @@ impl<W, C> Control<W, C> {
 }
 
 impl<T, W: Widget<T>, C: Controller<T, W>> Widget<T> for Control<W, C> {
-    fn layout(&mut self, parent: Size, ctx: &LayoutCtx<'_>, data: &T, env: &Env) -> Size {
-        self.widget.layout(parent, ctx, data, env)
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        self.widget.layout(constraints, ctx, data, env)
     }
 
     fn paint(&mut self, canvas: Canvas<'_>, data: &T) {
@@ -61,10 +78,18 @@ impl<T, W: Widget<T>, C: Controller<T, W>> Widget<T> for Control<W, C> {
         self.widget.contains(point)
     }
 
+    fn accepts_focus(&self) -> bool {
+        self.widget.accepts_focus()
+    }
+
     fn update(&mut self, ctx: &Context<'_>, data: &T) {
         self.controller.update(&mut self.widget, ctx, data)
     }
 
+    fn capture(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        self.controller.capture(&mut self.widget, event, ctx, data)
+    }
+
     fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
         self.controller.event(&mut self.widget, event, ctx, data)
     }
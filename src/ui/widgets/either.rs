@@ -0,0 +1,161 @@
+use std::cell::RefCell;
+use std::ops::ControlFlow;
+
+use crate::ui::*;
+
+/// Shows one of two children, depending on a predicate on the data.
+/// Only the child shown is laid out, painted and receives input. Both are updated,
+/// so that the hidden child is up to date when it's shown, but it can't be focused.
+pub struct Either<T> {
+    predicate: Box<dyn Fn(&T) -> bool>,
+    first: Pod<T, Box<dyn Widget<T>>>,
+    second: Pod<T, Box<dyn Widget<T>>>,
+    /// Whether the first child is shown, as of the last update.
+    current: bool,
+    /// Whether the child shown changed since the last pointer event.
+    switched: bool,
+}
+
+impl<T> Either<T> {
+    /// Show `first` when the predicate is `true`, and `second` otherwise.
+    pub fn new(
+        predicate: impl Fn(&T) -> bool + 'static,
+        first: impl Widget<T> + 'static,
+        second: impl Widget<T> + 'static,
+    ) -> Self {
+        Self {
+            predicate: Box::new(predicate),
+            first: Pod::new(Box::new(first)),
+            second: Pod::new(Box::new(second)),
+            current: true,
+            switched: false,
+        }
+    }
+
+    fn child(&self) -> &Pod<T, Box<dyn Widget<T>>> {
+        if self.current {
+            &self.first
+        } else {
+            &self.second
+        }
+    }
+
+    fn child_mut(&mut self) -> &mut Pod<T, Box<dyn Widget<T>>> {
+        if self.current {
+            &mut self.first
+        } else {
+            &mut self.second
+        }
+    }
+
+    fn hidden_mut(&mut self) -> &mut Pod<T, Box<dyn Widget<T>>> {
+        if self.current {
+            &mut self.second
+        } else {
+            &mut self.first
+        }
+    }
+}
+
+impl<T> Widget<T> for Either<T> {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        self.child_mut().layout(constraints, ctx, data, env)
+    }
+
+    fn paint(&mut self, mut canvas: Canvas<'_>, data: &T) {
+        let child = self.child_mut();
+        let size = child.size;
+
+        child.paint(canvas.resize(size), data);
+    }
+
+    fn update(&mut self, ctx: &Context<'_>, data: &T) {
+        let current = (self.predicate)(data);
+
+        if current != self.current {
+            self.current = current;
+            self.switched = true;
+            self.hidden_mut().hide_overlays(ctx);
+
+            ctx.request_layout();
+            ctx.request_paint();
+        }
+        self.child_mut().update(ctx, data);
+
+        // The hidden child isn't part of the focus chain, and loses focus if it had it.
+        let requests = RefCell::default();
+        self.hidden_mut().update(&ctx.requests(&requests), data);
+
+        let mut requests = requests.into_inner();
+        requests.focus_chain.clear();
+        requests.focused = false;
+
+        if let Some(parent) = ctx.requests {
+            parent.borrow_mut().merge(requests);
+        }
+    }
+
+    fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        match event {
+            // The hidden child may have had focus, or requested timers.
+            WidgetEvent::Focus(_) | WidgetEvent::Timer(_) => {
+                self.hidden_mut().event(event, ctx, data)?;
+            }
+            WidgetEvent::MouseEnter
+            | WidgetEvent::MouseExit
+            | WidgetEvent::MouseMove(_)
+            | WidgetEvent::MouseDown(_)
+            | WidgetEvent::MouseUp(_)
+            | WidgetEvent::MouseScroll(_)
+                if self.switched =>
+            {
+                self.switched = false;
+
+                // The child shown doesn't know whether it's under the cursor, since it
+                // was hidden, and the child hidden shouldn't remain hovered.
+                let (shown, hidden) = if self.current {
+                    (&mut self.first, &mut self.second)
+                } else {
+                    (&mut self.second, &mut self.first)
+                };
+                let _ = hidden.event(&WidgetEvent::MouseExit, ctx, data);
+                let _ = shown.event(&WidgetEvent::MouseMove(ctx.cursor), ctx, data);
+            }
+            _ => {}
+        }
+        self.child_mut().event(event, ctx, data)
+    }
+
+    fn lifecycle(
+        &mut self,
+        lifecycle: &WidgetLifecycle<'_>,
+        ctx: &Context<'_>,
+        data: &T,
+        env: &Env,
+    ) {
+        self.first.lifecycle(lifecycle, ctx, data, env);
+        self.second.lifecycle(lifecycle, ctx, data, env);
+    }
+
+    fn frame(&mut self, surfaces: &Surfaces, data: &mut T) {
+        self.child_mut().frame(surfaces, data);
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.child().contains(point)
+    }
+
+    fn cursor(&self) -> Option<&'static str> {
+        self.child().cursor()
+    }
+
+    fn display(&self) -> String {
+        format!("Either({})", self.child().display())
+    }
+}
@@ -0,0 +1,289 @@
+//! Flexible container, laying out children along an axis.
+//!
+//! ```
+//! use rgx::gfx::Rgba8;
+//! use rgx::ui::widgets::Flex;
+//! use rgx::ui::WidgetExt;
+//!
+//! // A toolbar, a content area taking up the remaining space, and a status bar.
+//! let column = Flex::<()>::column()
+//!     .child(Rgba8::RED.sized([64., 16.]))
+//!     .flex_child(Rgba8::WHITE, 1.)
+//!     .child(Rgba8::BLUE.sized([64., 8.]))
+//!     .spacing(4.);
+//! ```
+use std::ops::ControlFlow;
+
+use crate::gfx::Axis;
+use crate::ui::*;
+
+/// Alignment of children along the main axis of a [`Flex`] container.
+/// Only has an effect when there is space left over, ie. when no child is flexible.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum MainAxisAlignment {
+    /// Pack children at the start.
+    #[default]
+    Start,
+    /// Pack children in the center.
+    Center,
+    /// Pack children at the end.
+    End,
+    /// Distribute the space between children.
+    SpaceBetween,
+    /// Distribute the space around children, with half a space at either end.
+    SpaceAround,
+    /// Distribute the space evenly between children and at either end.
+    SpaceEvenly,
+}
+
+/// Alignment of children along the cross axis of a [`Flex`] container.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum CrossAxisAlignment {
+    /// Align children to the start.
+    #[default]
+    Start,
+    /// Center children.
+    Center,
+    /// Align children to the end.
+    End,
+}
+
+struct Child<T> {
+    widget: Pod<T, Box<dyn Widget<T>>>,
+    /// Flex factor. Children with a factor of zero are laid out at their own size.
+    flex: f32,
+}
+
+/// Flexible container. Children are laid out one after the other along the main axis.
+/// Flexible children share the space that is left over, in proportion to their
+/// flex factor. Without a bound on the main axis, eg. in a [`Scroll`](super::Scroll),
+/// there is no space to share, and flexible children are laid out at their own size.
+pub struct Flex<T> {
+    axis: Axis,
+    size: Size,
+    spacing: f32,
+    main_alignment: MainAxisAlignment,
+    cross_alignment: CrossAxisAlignment,
+    children: Vec<Child<T>>,
+}
+
+impl<T> Flex<T> {
+    /// Create a new container along the given axis.
+    pub fn new(axis: Axis) -> Self {
+        Self {
+            axis,
+            size: Size::ZERO,
+            spacing: 0.,
+            main_alignment: MainAxisAlignment::default(),
+            cross_alignment: CrossAxisAlignment::default(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Create a horizontal container.
+    pub fn row() -> Self {
+        Self::new(Axis::Horizontal)
+    }
+
+    /// Create a vertical container.
+    pub fn column() -> Self {
+        Self::new(Axis::Vertical)
+    }
+
+    /// Add a child that is laid out at its own size.
+    pub fn child(self, child: impl Widget<T> + 'static) -> Self {
+        self.flex_child(child, 0.)
+    }
+
+    /// Add a child that takes a share of the leftover space, in proportion to its
+    /// flex factor.
+    pub fn flex_child(mut self, child: impl Widget<T> + 'static, flex: f32) -> Self {
+        self.push(child, flex);
+        self
+    }
+
+    /// Add a child with the given flex factor.
+    pub fn push(&mut self, child: impl Widget<T> + 'static, flex: f32) {
+        self.children.push(Child {
+            widget: Pod::new(Box::new(child)),
+            flex: flex.max(0.),
+        });
+    }
+
+    /// Set the space between children.
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Set the main axis alignment.
+    pub fn main_axis_alignment(mut self, alignment: MainAxisAlignment) -> Self {
+        self.main_alignment = alignment;
+        self
+    }
+
+    /// Set the cross axis alignment.
+    pub fn cross_axis_alignment(mut self, alignment: CrossAxisAlignment) -> Self {
+        self.cross_alignment = alignment;
+        self
+    }
+
+    pub fn bounds(&self) -> Rect<f32> {
+        Rect::origin(self.size)
+    }
+}
+
+/// Split a size into its main and cross components.
+fn split(axis: Axis, size: Size) -> (f32, f32) {
+    match axis {
+        Axis::Horizontal => (size.w, size.h),
+        Axis::Vertical => (size.h, size.w),
+    }
+}
+
+/// Create a size from its main and cross components.
+fn join(axis: Axis, main: f32, cross: f32) -> Size {
+    match axis {
+        Axis::Horizontal => Size::new(main, cross),
+        Axis::Vertical => Size::new(cross, main),
+    }
+}
+
+impl<T> Widget<T> for Flex<T> {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        let axis = self.axis;
+        let (parent_main, parent_cross) = split(axis, constraints.max);
+        let spacing = self.spacing * self.children.len().saturating_sub(1) as f32;
+        let total_flex: f32 = self.children.iter().map(|c| c.flex).sum();
+
+        // Lay out inflexible children first, to find out how much space is left.
+        let mut used = spacing;
+        for child in self.children.iter_mut().filter(|c| c.flex == 0.) {
+            let available =
+                BoxConstraints::loose(join(axis, (parent_main - used).max(0.), parent_cross));
+            let size = child.widget.layout(available, ctx, data, env);
+
+            used += split(axis, size).0;
+        }
+
+        // Then share the remaining space between flexible children. Without a bound on
+        // the main axis, there is no remaining space to share, so they get their own size.
+        let remaining = (parent_main - used).max(0.);
+        for child in self.children.iter_mut().filter(|c| c.flex > 0.) {
+            let available = if parent_main.is_finite() {
+                let share = remaining * child.flex / total_flex;
+                BoxConstraints::new(join(axis, share, 0.), join(axis, share, parent_cross))
+            } else {
+                BoxConstraints::loose(join(axis, parent_main, parent_cross))
+            };
+            let size = child.widget.layout(available, ctx, data, env);
+
+            used += split(axis, size).0;
+        }
+
+        let cross = self
+            .children
+            .iter()
+            .map(|c| split(axis, c.widget.size).1)
+            .fold(0., f32::max);
+        let (min_main, _) = split(axis, constraints.min);
+        let main = if parent_main.is_finite()
+            && (total_flex > 0. || self.main_alignment != MainAxisAlignment::Start)
+        {
+            parent_main.max(used)
+        } else {
+            used.max(min_main)
+        };
+
+        // Position children.
+        let free = (main - used).max(0.);
+        let n = self.children.len() as f32;
+        let (mut pos, gap) = match self.main_alignment {
+            MainAxisAlignment::Start => (0., 0.),
+            MainAxisAlignment::Center => (free / 2., 0.),
+            MainAxisAlignment::End => (free, 0.),
+            MainAxisAlignment::SpaceBetween if n > 1. => (0., free / (n - 1.)),
+            MainAxisAlignment::SpaceBetween => (0., 0.),
+            MainAxisAlignment::SpaceAround => (free / n / 2., free / n),
+            MainAxisAlignment::SpaceEvenly => (free / (n + 1.), free / (n + 1.)),
+        };
+
+        for i in 0..self.children.len() {
+            let (child_main, child_cross) = split(axis, self.children[i].widget.size);
+            let offset = match self.cross_alignment {
+                CrossAxisAlignment::Start => 0.,
+                CrossAxisAlignment::Center => (cross - child_cross) / 2.,
+                CrossAxisAlignment::End => cross - child_cross,
+            };
+            let offset = join(axis, pos, offset);
+
+            self.children[i].widget.offset = Offset::new(offset.w, offset.h);
+            pos += child_main + self.spacing + gap;
+        }
+        self.size = constraints.constrain(join(axis, main, cross));
+        self.size
+    }
+
+    fn paint(&mut self, mut canvas: Canvas<'_>, data: &T) {
+        for child in &mut self.children {
+            let size = child.widget.size;
+            child.widget.paint(canvas.resize(size), data);
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<'_>, data: &T) {
+        for child in &mut self.children {
+            child.widget.update(ctx, data);
+        }
+    }
+
+    fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        for child in &mut self.children {
+            if let flow @ ControlFlow::Break(_) = child.widget.event(event, ctx, data) {
+                return flow;
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn lifecycle(
+        &mut self,
+        lifecycle: &WidgetLifecycle<'_>,
+        ctx: &Context<'_>,
+        data: &T,
+        env: &Env,
+    ) {
+        for child in &mut self.children {
+            child.widget.lifecycle(lifecycle, ctx, data, env);
+        }
+    }
+
+    fn frame(&mut self, surfaces: &Surfaces, data: &mut T) {
+        for child in &mut self.children {
+            child.widget.frame(surfaces, data);
+        }
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.bounds().contains(point)
+    }
+
+    fn cursor(&self) -> Option<&'static str> {
+        for child in &self.children {
+            if child.widget.hot {
+                return child.widget.cursor();
+            }
+        }
+        None
+    }
+
+    fn display(&self) -> String {
+        format!("Flex({:?}, {})", self.axis, self.children.len())
+    }
+}
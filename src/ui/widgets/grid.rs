@@ -0,0 +1,495 @@
+//! Grid container, laying out children in rows and columns.
+//!
+//! ```
+//! use rgx::gfx::Rgba8;
+//! use rgx::ui::widgets::{CellAlignment, Grid, GridItem, TrackSize};
+//! use rgx::ui::WidgetExt;
+//!
+//! // A palette with a swatch spanning the top row.
+//! let palette = Grid::<()>::new()
+//!     .columns([TrackSize::Fixed(16.); 4])
+//!     .rows([TrackSize::Fixed(8.)])
+//!     .spacing(2.)
+//!     .item(
+//!         GridItem::new(Rgba8::WHITE)
+//!             .span(1, 4)
+//!             .align(CellAlignment::Fill, CellAlignment::Fill),
+//!     )
+//!     .child(Rgba8::RED.sized([16., 16.]))
+//!     .child(Rgba8::GREEN.sized([16., 16.]))
+//!     .child(Rgba8::BLUE.sized([16., 16.]))
+//!     .child(Rgba8::BLACK.sized([16., 16.]));
+//! ```
+use std::collections::HashSet;
+use std::ops::ControlFlow;
+
+use crate::ui::*;
+
+/// Size of a grid row or column.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum TrackSize {
+    /// Sized to fit the largest cell in the track.
+    #[default]
+    Auto,
+    /// Fixed size.
+    Fixed(f32),
+}
+
+impl From<f32> for TrackSize {
+    fn from(size: f32) -> Self {
+        Self::Fixed(size)
+    }
+}
+
+/// Alignment of a child within its grid cell, along one axis.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum CellAlignment {
+    /// Align to the start of the cell.
+    #[default]
+    Start,
+    /// Center in the cell.
+    Center,
+    /// Align to the end of the cell.
+    End,
+    /// Stretch the child to fill the cell.
+    Fill,
+}
+
+impl CellAlignment {
+    /// Offset of a child of the given size in a cell of the given size.
+    fn offset(self, cell: f32, child: f32) -> f32 {
+        match self {
+            Self::Start | Self::Fill => 0.,
+            Self::Center => (cell - child) / 2.,
+            Self::End => cell - child,
+        }
+    }
+}
+
+/// A grid child, with its placement.
+pub struct GridItem<T> {
+    widget: Pod<T, Box<dyn Widget<T>>>,
+    /// Row and column of the top-left cell. Placed automatically if not set.
+    cell: Option<(usize, usize)>,
+    /// Number of rows and columns spanned.
+    span: (usize, usize),
+    /// Horizontal and vertical alignment. Uses the grid alignment if not set.
+    alignment: Option<(CellAlignment, CellAlignment)>,
+}
+
+impl<T> GridItem<T> {
+    pub fn new(widget: impl Widget<T> + 'static) -> Self {
+        Self {
+            widget: Pod::new(Box::new(widget)),
+            cell: None,
+            span: (1, 1),
+            alignment: None,
+        }
+    }
+
+    /// Place the item at the given row and column.
+    pub fn at(self, row: usize, column: usize) -> Self {
+        Self {
+            cell: Some((row, column)),
+            ..self
+        }
+    }
+
+    /// Span the given number of rows and columns.
+    pub fn span(self, rows: usize, columns: usize) -> Self {
+        Self {
+            span: (rows.max(1), columns.max(1)),
+            ..self
+        }
+    }
+
+    /// Align the item within its cell.
+    pub fn align(self, horizontal: CellAlignment, vertical: CellAlignment) -> Self {
+        Self {
+            alignment: Some((horizontal, vertical)),
+            ..self
+        }
+    }
+}
+
+/// Grid container. Children are placed in cells, either explicitly with
+/// [`GridItem::at`], or in the next free cell, row by row. Automatic placement
+/// happens during layout, so it doesn't depend on the order in which the grid
+/// is configured.
+///
+/// Rows and columns without a size, eg. rows added to fit the children, are sized
+/// automatically.
+pub struct Grid<T> {
+    size: Size,
+    columns: Vec<TrackSize>,
+    rows: Vec<TrackSize>,
+    column_spacing: f32,
+    row_spacing: f32,
+    alignment: (CellAlignment, CellAlignment),
+    items: Vec<GridItem<T>>,
+    /// Start of each column, as of the last layout.
+    column_offsets: Vec<f32>,
+    /// Start of each row, as of the last layout.
+    row_offsets: Vec<f32>,
+}
+
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn new() -> Self {
+        Self {
+            size: Size::ZERO,
+            columns: Vec::new(),
+            rows: Vec::new(),
+            column_spacing: 0.,
+            row_spacing: 0.,
+            alignment: Default::default(),
+            items: Vec::new(),
+            column_offsets: Vec::new(),
+            row_offsets: Vec::new(),
+        }
+    }
+
+    /// Set the column sizes. This also sets the number of columns used when placing
+    /// children automatically.
+    pub fn columns<S: Into<TrackSize>>(mut self, columns: impl IntoIterator<Item = S>) -> Self {
+        self.columns = columns.into_iter().map(S::into).collect();
+        self
+    }
+
+    /// Set the row sizes.
+    pub fn rows<S: Into<TrackSize>>(mut self, rows: impl IntoIterator<Item = S>) -> Self {
+        self.rows = rows.into_iter().map(S::into).collect();
+        self
+    }
+
+    /// Set the space between rows and between columns.
+    pub fn spacing(self, spacing: f32) -> Self {
+        self.row_spacing(spacing).column_spacing(spacing)
+    }
+
+    /// Set the space between rows.
+    pub fn row_spacing(mut self, spacing: f32) -> Self {
+        self.row_spacing = spacing;
+        self
+    }
+
+    /// Set the space between columns.
+    pub fn column_spacing(mut self, spacing: f32) -> Self {
+        self.column_spacing = spacing;
+        self
+    }
+
+    /// Set the default alignment of children within their cells.
+    pub fn align(mut self, horizontal: CellAlignment, vertical: CellAlignment) -> Self {
+        self.alignment = (horizontal, vertical);
+        self
+    }
+
+    /// Add a child in the next free cell.
+    pub fn child(self, child: impl Widget<T> + 'static) -> Self {
+        self.item(GridItem::new(child))
+    }
+
+    /// Add a child at the given row and column.
+    pub fn cell(self, row: usize, column: usize, child: impl Widget<T> + 'static) -> Self {
+        self.item(GridItem::new(child).at(row, column))
+    }
+
+    /// Add an item.
+    pub fn item(mut self, item: GridItem<T>) -> Self {
+        self.push(item);
+        self
+    }
+
+    /// Add an item. Items without a cell are placed in the first free cell, row by row,
+    /// where their span fits, after the items with a cell.
+    pub fn push(&mut self, item: GridItem<T>) {
+        self.items.push(item);
+    }
+
+    /// Number of rows, including the ones added to fit the children.
+    pub fn row_count(&self) -> usize {
+        self.items
+            .iter()
+            .zip(self.cells())
+            .map(|(i, (r, _))| r + i.span.0)
+            .fold(self.rows.len(), usize::max)
+    }
+
+    /// Number of columns, including the ones added to fit the children.
+    pub fn column_count(&self) -> usize {
+        self.items
+            .iter()
+            .zip(self.cells())
+            .map(|(i, (_, c))| c + i.span.1)
+            .fold(self.columns.len(), usize::max)
+    }
+
+    /// Get the row and column of the cell containing the given point, if any.
+    pub fn cell_at(&self, point: Point) -> Option<(usize, usize)> {
+        if !self.bounds().contains(point) {
+            return None;
+        }
+        let row = self.row_offsets.iter().rposition(|&y| y <= point.y)?;
+        let column = self.column_offsets.iter().rposition(|&x| x <= point.x)?;
+
+        Some((row, column))
+    }
+
+    pub fn bounds(&self) -> Rect<f32> {
+        Rect::origin(self.size)
+    }
+
+    /// Top-left cell of each item. Items with a cell are placed first, then the
+    /// others are placed in the first free cell, row by row, where their span fits.
+    /// Automatic placement uses the number of columns set with [`Grid::columns`].
+    fn cells(&self) -> Vec<(usize, usize)> {
+        let columns = self.columns.len().max(1);
+        let mut occupied = HashSet::new();
+        let occupy = |occupied: &mut HashSet<_>, (row, column), (rows, cols)| {
+            for r in row..row + rows {
+                for c in column..column + cols {
+                    occupied.insert((r, c));
+                }
+            }
+        };
+        for item in &self.items {
+            if let Some(cell) = item.cell {
+                occupy(&mut occupied, cell, item.span);
+            }
+        }
+        self.items
+            .iter()
+            .map(|item| {
+                if let Some(cell) = item.cell {
+                    return cell;
+                }
+                let (rows, cols) = item.span;
+                let fits = |row: usize, column: usize| {
+                    column + cols <= columns.max(cols)
+                        && (row..row + rows)
+                            .all(|r| (column..column + cols).all(|c| !occupied.contains(&(r, c))))
+                };
+                // There are always free cells below the occupied ones.
+                let cell = (0..)
+                    .flat_map(|row| (0..columns).map(move |column| (row, column)))
+                    .find(|&(row, column)| fits(row, column))
+                    .unwrap_or_default();
+
+                occupy(&mut occupied, cell, item.span);
+                cell
+            })
+            .collect()
+    }
+}
+
+/// Size of the given tracks, including the spacing between them.
+fn extent(tracks: &[f32], spacing: f32) -> f32 {
+    tracks.iter().sum::<f32>() + spacing * (tracks.len() - 1) as f32
+}
+
+/// Size the tracks spanned by an item so that they fit the given size, by growing the
+/// automatic tracks equally.
+fn fit(tracks: &mut [f32], auto: &[bool], spacing: f32, size: f32) {
+    let current = extent(tracks, spacing);
+    let count = auto.iter().filter(|a| **a).count();
+
+    if size > current && size.is_finite() && count > 0 {
+        let extra = (size - current) / count as f32;
+
+        for (track, _) in tracks.iter_mut().zip(auto).filter(|(_, a)| **a) {
+            *track += extra;
+        }
+    }
+}
+
+/// Start of each track, and the total size, given the track sizes and spacing.
+fn offsets(tracks: &[f32], spacing: f32) -> (Vec<f32>, f32) {
+    let mut offsets = Vec::with_capacity(tracks.len());
+    let mut pos = 0.;
+
+    for track in tracks {
+        offsets.push(pos);
+        pos += track + spacing;
+    }
+    (offsets, (pos - spacing).max(0.))
+}
+
+impl<T> Widget<T> for Grid<T> {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        let resolve = |tracks: &[TrackSize], count: usize| -> (Vec<f32>, Vec<bool>) {
+            (0..count)
+                .map(|i| match tracks.get(i).copied().unwrap_or_default() {
+                    TrackSize::Fixed(size) => (size, false),
+                    TrackSize::Auto => (0., true),
+                })
+                .unzip()
+        };
+        let cells = self.cells();
+        let (mut rows, auto_rows) = resolve(&self.rows, self.row_count());
+        let (mut columns, auto_columns) = resolve(&self.columns, self.column_count());
+
+        // Measure children, then grow the automatic tracks to fit them. Children are
+        // bounded by the tracks they span if those are all fixed, and unbounded otherwise,
+        // so that children which fill the space they're given don't grow the tracks.
+        // Items spanning a single track are fitted first, so that spanning items only
+        // grow tracks when needed.
+        for (item, &(row, column)) in self.items.iter_mut().zip(&cells) {
+            let (nrows, ncolumns) = item.span;
+            let bound = |tracks: &[f32], auto: &[bool], spacing: f32| {
+                if auto.iter().any(|a| *a) {
+                    f32::INFINITY
+                } else {
+                    extent(tracks, spacing)
+                }
+            };
+            let max = Size::new(
+                bound(
+                    &columns[column..column + ncolumns],
+                    &auto_columns[column..column + ncolumns],
+                    self.column_spacing,
+                ),
+                bound(
+                    &rows[row..row + nrows],
+                    &auto_rows[row..row + nrows],
+                    self.row_spacing,
+                ),
+            );
+            item.widget
+                .layout(BoxConstraints::loose(max), ctx, data, env);
+        }
+        let mut items = self.items.iter().zip(&cells).collect::<Vec<_>>();
+        items.sort_by_key(|(i, _)| i.span.0.max(i.span.1));
+
+        for (item, &(row, column)) in items {
+            let (nrows, ncolumns) = item.span;
+            let size = item.widget.size;
+
+            fit(
+                &mut rows[row..row + nrows],
+                &auto_rows[row..row + nrows],
+                self.row_spacing,
+                size.h,
+            );
+            fit(
+                &mut columns[column..column + ncolumns],
+                &auto_columns[column..column + ncolumns],
+                self.column_spacing,
+                size.w,
+            );
+        }
+
+        let (row_offsets, height) = offsets(&rows, self.row_spacing);
+        let (column_offsets, width) = offsets(&columns, self.column_spacing);
+
+        // Lay out children in their cells, now that the tracks are resolved, and
+        // position them.
+        for (item, &(row, column)) in self.items.iter_mut().zip(&cells) {
+            let (nrows, ncolumns) = item.span;
+            let (horizontal, vertical) = item.alignment.unwrap_or(self.alignment);
+            let (x, y) = (column_offsets[column], row_offsets[row]);
+            let cell = Size::new(
+                column_offsets
+                    .get(column + ncolumns)
+                    .map_or(width, |x| x - self.column_spacing)
+                    - x,
+                row_offsets
+                    .get(row + nrows)
+                    .map_or(height, |y| y - self.row_spacing)
+                    - y,
+            );
+
+            let mut cell_constraints = BoxConstraints::loose(cell);
+
+            if horizontal == CellAlignment::Fill {
+                cell_constraints.min.w = cell.w;
+            }
+            if vertical == CellAlignment::Fill {
+                cell_constraints.min.h = cell.h;
+            }
+            let size = item.widget.layout(cell_constraints, ctx, data, env);
+
+            item.widget.offset = Offset::new(
+                x + horizontal.offset(cell.w, size.w),
+                y + vertical.offset(cell.h, size.h),
+            );
+        }
+        self.row_offsets = row_offsets;
+        self.column_offsets = column_offsets;
+        self.size = constraints.constrain([width, height]);
+        self.size
+    }
+
+    fn paint(&mut self, mut canvas: Canvas<'_>, data: &T) {
+        for item in &mut self.items {
+            let size = item.widget.size;
+            item.widget.paint(canvas.resize(size), data);
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<'_>, data: &T) {
+        for item in &mut self.items {
+            item.widget.update(ctx, data);
+        }
+    }
+
+    fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        for item in &mut self.items {
+            if let flow @ ControlFlow::Break(_) = item.widget.event(event, ctx, data) {
+                return flow;
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn lifecycle(
+        &mut self,
+        lifecycle: &WidgetLifecycle<'_>,
+        ctx: &Context<'_>,
+        data: &T,
+        env: &Env,
+    ) {
+        for item in &mut self.items {
+            item.widget.lifecycle(lifecycle, ctx, data, env);
+        }
+    }
+
+    fn frame(&mut self, surfaces: &Surfaces, data: &mut T) {
+        for item in &mut self.items {
+            item.widget.frame(surfaces, data);
+        }
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.bounds().contains(point)
+    }
+
+    fn cursor(&self) -> Option<&'static str> {
+        for item in &self.items {
+            if item.widget.hot {
+                return item.widget.cursor();
+            }
+        }
+        None
+    }
+
+    fn display(&self) -> String {
+        format!(
+            "Grid({}x{}, {})",
+            self.row_count(),
+            self.column_count(),
+            self.items.len()
+        )
+    }
+}
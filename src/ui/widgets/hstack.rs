@@ -48,24 +48,35 @@ impl<T> HStack<T> {
 }
 
 impl<T> Widget<T> for HStack<T> {
-    fn layout(&mut self, parent: Size, ctx: &LayoutCtx<'_>, data: &T, env: &Env) -> Size {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        let parent = constraints.max;
         let mut offset = Vector::ZERO;
-        let mut height: f32 = 0.;
+        let mut width: f32 = 0.;
+        // Height of the current row.
+        let mut row: f32 = 0.;
 
         for widget in &mut self.children {
-            widget.layout(parent, ctx, data, env);
-            height = height.max(widget.size.h);
+            widget.layout(constraints.loosen(), ctx, data, env);
 
-            if offset.x + widget.size.w > parent.w {
-                offset.y += widget.size.h;
+            // Wrap onto the next row, unless this is the first widget of the row.
+            if offset.x > 0. && offset.x + widget.size.w > parent.w {
+                offset.y += row;
                 offset.x = 0.;
-
-                height += widget.size.h;
+                row = 0.;
             }
             widget.offset = offset;
             offset.x += widget.size.w + self.spacing;
+
+            row = row.max(widget.size.h);
+            width = width.max(offset.x - self.spacing);
         }
-        self.size = Size::new(offset.x - self.spacing, height);
+        self.size = constraints.constrain([width, offset.y + row]);
         self.size
     }
 
@@ -16,11 +16,17 @@ impl Image {
 }
 
 impl<T> Widget<T> for Image {
-    fn layout(&mut self, _parent: Size, _ctx: &LayoutCtx<'_>, _data: &T, _env: &Env) -> Size {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        _ctx: &LayoutCtx<'_>,
+        _data: &T,
+        _env: &Env,
+    ) -> Size {
         if let Self::ById(_, info) = self {
-            return info.size.into();
+            return constraints.constrain(info.size);
         }
-        Size::ZERO
+        constraints.smallest()
     }
 
     fn paint(&mut self, mut canvas: Canvas<'_>, _data: &T) {
@@ -0,0 +1,123 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::ui::lens::Lens;
+use crate::ui::*;
+
+/// A widget of `U`, used as a widget of `T` through a [`Lens`].
+///
+/// Widgets of `U` shown in the overlay layer by the wrapped widget are wrapped
+/// with the same lens.
+pub struct LensWrap<T, U, L, W> {
+    lens: Rc<L>,
+    widget: W,
+    data: PhantomData<fn(T) -> U>,
+}
+
+impl<T, U, L: Lens<T, U>, W: Widget<U>> LensWrap<T, U, L, W> {
+    pub fn new(widget: W, lens: L) -> Self {
+        Self {
+            lens: Rc::new(lens),
+            widget,
+            data: PhantomData,
+        }
+    }
+}
+
+impl<T: 'static, U: 'static, L: Lens<T, U> + 'static, W: Widget<U>> LensWrap<T, U, L, W> {
+    /// Pass on the requests made by the wrapped widget.
+    fn requests(&self, requests: RefCell<Requests>, ctx: &Context<'_>) {
+        ctx.lift_requests(requests, |widget: Box<dyn Widget<U>>| {
+            Box::new(LensWrap {
+                lens: self.lens.clone(),
+                widget,
+                data: PhantomData,
+            }) as Box<dyn Widget<T>>
+        });
+    }
+}
+
+impl<T: 'static, U: 'static, L: Lens<T, U> + 'static, W: Widget<U>> Widget<T>
+    for LensWrap<T, U, L, W>
+{
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        self.widget
+            .layout(constraints, ctx, self.lens.get(data), env)
+    }
+
+    fn paint(&mut self, canvas: Canvas<'_>, data: &T) {
+        self.widget.paint(canvas, self.lens.get(data))
+    }
+
+    fn update(&mut self, ctx: &Context<'_>, data: &T) {
+        let requests = RefCell::default();
+
+        self.widget
+            .update(&ctx.requests(&requests), self.lens.get(data));
+        self.requests(requests, ctx);
+    }
+
+    fn capture(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        let requests = RefCell::default();
+        let flow = self
+            .widget
+            .capture(event, &ctx.requests(&requests), self.lens.get_mut(data));
+        self.requests(requests, ctx);
+
+        flow
+    }
+
+    fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        let requests = RefCell::default();
+        let flow = self
+            .widget
+            .event(event, &ctx.requests(&requests), self.lens.get_mut(data));
+        self.requests(requests, ctx);
+
+        flow
+    }
+
+    fn lifecycle(
+        &mut self,
+        lifecycle: &WidgetLifecycle<'_>,
+        ctx: &Context<'_>,
+        data: &T,
+        env: &Env,
+    ) {
+        let requests = RefCell::default();
+
+        self.widget.lifecycle(
+            lifecycle,
+            &ctx.requests(&requests),
+            self.lens.get(data),
+            env,
+        );
+        self.requests(requests, ctx);
+    }
+
+    fn frame(&mut self, surfaces: &Surfaces, data: &mut T) {
+        self.widget.frame(surfaces, self.lens.get_mut(data))
+    }
+
+    fn cursor(&self) -> Option<&'static str> {
+        self.widget.cursor()
+    }
+
+    fn accepts_focus(&self) -> bool {
+        self.widget.accepts_focus()
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.widget.contains(point)
+    }
+
+    fn display(&self) -> String {
+        format!("LensWrap({})", self.widget.display())
+    }
+}
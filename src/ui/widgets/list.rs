@@ -0,0 +1,429 @@
+//! Dynamic list of widgets, built from a collection in the data.
+//!
+//! ```
+//! use rgx::gfx::Rgba8;
+//! use rgx::ui::widgets::List;
+//! use rgx::ui::{Interact, WidgetExt};
+//!
+//! struct Layer {
+//!     id: u64,
+//!     visible: bool,
+//! }
+//!
+//! struct Data {
+//!     layers: Vec<Layer>,
+//! }
+//!
+//! // One row per layer, toggling the layer's visibility when clicked.
+//! let list = List::new(
+//!     |data: &Data| &data.layers,
+//!     |data: &mut Data| &mut data.layers,
+//!     |layer| layer.id,
+//!     |_layer| {
+//!         Rgba8::GREY
+//!             .sized([64., 16.])
+//!             .on_click(|_, layer: &mut Layer| layer.visible = !layer.visible)
+//!     },
+//! )
+//! .spacing(1.);
+//! ```
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::ControlFlow;
+use std::rc::Rc;
+
+use crate::gfx::Axis;
+use crate::ui::lens::{Field, Lens};
+use crate::ui::*;
+
+/// A child of a list, built for the item with the given key.
+struct Child<I, K> {
+    key: K,
+    widget: Pod<I, Box<dyn Widget<I>>>,
+}
+
+/// List of widgets, one per item of a collection in the data. Each child widget
+/// receives its item as data.
+///
+/// When the collection changes, children are matched with items by key: children of
+/// removed items are dropped, and children are built for new items. New children are
+/// initialized before their first layout. Dropped children lose keyboard focus, and
+/// the widgets they showed in the overlay layer are hidden.
+///
+/// Keys should be unique. Items with duplicate keys get a child each, but only one of
+/// these children is kept when the collection changes.
+///
+/// Widgets shown in the overlay layer by a child receive the child's item.
+pub struct List<T, I, K> {
+    /// Lens to the items.
+    items: Rc<dyn Lens<T, Vec<I>>>,
+    /// Get the key identifying an item.
+    key: Rc<dyn Fn(&I) -> K>,
+    /// Build the widget for an item.
+    build: Box<dyn Fn(&I) -> Box<dyn Widget<I>>>,
+    children: Vec<Child<I, K>>,
+    axis: Axis,
+    spacing: f32,
+    size: Size,
+}
+
+impl<T, I, K: Hash + Eq> List<T, I, K> {
+    /// Create a vertical list of the items returned by the given accessors.
+    pub fn new<W: Widget<I> + 'static>(
+        items: impl Fn(&T) -> &Vec<I> + 'static,
+        items_mut: impl Fn(&mut T) -> &mut Vec<I> + 'static,
+        key: impl Fn(&I) -> K + 'static,
+        build: impl Fn(&I) -> W + 'static,
+    ) -> Self {
+        Self::with_lens(Field::new(items, items_mut), key, build)
+    }
+
+    /// Create a vertical list of the items selected with a [`Lens`].
+    pub fn with_lens<W: Widget<I> + 'static>(
+        items: impl Lens<T, Vec<I>> + 'static,
+        key: impl Fn(&I) -> K + 'static,
+        build: impl Fn(&I) -> W + 'static,
+    ) -> Self {
+        Self {
+            items: Rc::new(items),
+            key: Rc::new(key),
+            build: Box::new(move |item| Box::new(build(item))),
+            children: Vec::new(),
+            axis: Axis::Vertical,
+            spacing: 0.,
+            size: Size::ZERO,
+        }
+    }
+
+    /// Lay out the children horizontally.
+    pub fn horizontal(self) -> Self {
+        Self {
+            axis: Axis::Horizontal,
+            ..self
+        }
+    }
+
+    pub fn spacing(self, spacing: f32) -> Self {
+        Self { spacing, ..self }
+    }
+
+    /// Number of children.
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Whether the list has no children.
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    pub fn bounds(&self) -> Rect<f32> {
+        Rect::origin(self.size)
+    }
+
+    /// Match children with the given items, building and dropping children as needed.
+    /// Returns whether the children changed.
+    fn reconcile(&mut self, items: &[I], ctx: &Context<'_>) -> bool {
+        if self.children.len() == items.len()
+            && self
+                .children
+                .iter()
+                .zip(items)
+                .all(|(child, item)| child.key == (self.key)(item))
+        {
+            return false;
+        }
+        let mut children: HashMap<K, Pod<I, Box<dyn Widget<I>>>> = HashMap::new();
+
+        for child in self.children.drain(..) {
+            // With duplicate keys, only one child can be kept, the others are dropped.
+            if let Some(mut displaced) = children.insert(child.key, child.widget) {
+                displaced.remove(ctx);
+            }
+        }
+
+        self.children = items
+            .iter()
+            .map(|item| {
+                let key = (self.key)(item);
+                let widget = children
+                    .remove(&key)
+                    .unwrap_or_else(|| Pod::new((self.build)(item)));
+
+                Child { key, widget }
+            })
+            .collect();
+
+        // Dropped children lose focus as they're no longer updated, but have to be
+        // removed from the tree, which hides their overlays.
+        for (_, mut widget) in children {
+            widget.remove(ctx);
+        }
+        true
+    }
+}
+
+/// Get the widget of an item as a widget of the list data, so that it can be shown
+/// in the overlay layer.
+fn lift<T: 'static, I: 'static, K: PartialEq + Clone + 'static>(
+    items: &Rc<dyn Lens<T, Vec<I>>>,
+    key: &Rc<dyn Fn(&I) -> K>,
+    item: &K,
+) -> impl Fn(Box<dyn Widget<I>>) -> Box<dyn Widget<T>> {
+    let (items, key, item) = (items.clone(), key.clone(), item.clone());
+
+    move |widget| {
+        Box::new(ItemWrap {
+            items: items.clone(),
+            key: key.clone(),
+            item: item.clone(),
+            widget,
+        })
+    }
+}
+
+/// A widget of a list item, used as a widget of the list data. The item is found by
+/// key. If it's no longer in the list, the widget is empty.
+struct ItemWrap<T, I, K> {
+    items: Rc<dyn Lens<T, Vec<I>>>,
+    key: Rc<dyn Fn(&I) -> K>,
+    /// Key of the item.
+    item: K,
+    widget: Box<dyn Widget<I>>,
+}
+
+impl<T: 'static, I: 'static, K: PartialEq + Clone + 'static> ItemWrap<T, I, K> {
+    fn get<'a>(&self, data: &'a T) -> Option<&'a I> {
+        self.items
+            .get(data)
+            .iter()
+            .find(|i| (self.key)(i) == self.item)
+    }
+
+    fn get_mut<'a>(&self, data: &'a mut T) -> Option<&'a mut I> {
+        self.items
+            .get_mut(data)
+            .iter_mut()
+            .find(|i| (self.key)(i) == self.item)
+    }
+
+    /// Pass on the requests made by the wrapped widget.
+    fn requests(&self, requests: RefCell<Requests>, ctx: &Context<'_>) {
+        ctx.lift_requests(requests, lift(&self.items, &self.key, &self.item));
+    }
+}
+
+impl<T: 'static, I: 'static, K: PartialEq + Clone + 'static> Widget<T> for ItemWrap<T, I, K> {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        match self.get(data) {
+            Some(item) => self.widget.layout(constraints, ctx, item, env),
+            None => constraints.smallest(),
+        }
+    }
+
+    fn paint(&mut self, canvas: Canvas<'_>, data: &T) {
+        if let Some(item) = self.get(data) {
+            self.widget.paint(canvas, item);
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<'_>, data: &T) {
+        if let Some(item) = self.get(data) {
+            let requests = RefCell::default();
+
+            self.widget.update(&ctx.requests(&requests), item);
+            self.requests(requests, ctx);
+        }
+    }
+
+    fn capture(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        let Some(item) = self.get_mut(data) else {
+            return ControlFlow::Continue(());
+        };
+        let requests = RefCell::default();
+        let flow = self.widget.capture(event, &ctx.requests(&requests), item);
+        self.requests(requests, ctx);
+
+        flow
+    }
+
+    fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        let Some(item) = self.get_mut(data) else {
+            return ControlFlow::Continue(());
+        };
+        let requests = RefCell::default();
+        let flow = self.widget.event(event, &ctx.requests(&requests), item);
+        self.requests(requests, ctx);
+
+        flow
+    }
+
+    fn lifecycle(
+        &mut self,
+        lifecycle: &WidgetLifecycle<'_>,
+        ctx: &Context<'_>,
+        data: &T,
+        env: &Env,
+    ) {
+        if let Some(item) = self.get(data) {
+            let requests = RefCell::default();
+
+            self.widget
+                .lifecycle(lifecycle, &ctx.requests(&requests), item, env);
+            self.requests(requests, ctx);
+        }
+    }
+
+    fn frame(&mut self, surfaces: &Surfaces, data: &mut T) {
+        if let Some(item) = self.get_mut(data) {
+            self.widget.frame(surfaces, item);
+        }
+    }
+
+    fn cursor(&self) -> Option<&'static str> {
+        self.widget.cursor()
+    }
+
+    fn accepts_focus(&self) -> bool {
+        self.widget.accepts_focus()
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.widget.contains(point)
+    }
+
+    fn display(&self) -> String {
+        format!("ItemWrap({})", self.widget.display())
+    }
+}
+
+impl<T: 'static, I: 'static, K: Hash + Eq + Clone + 'static> Widget<T> for List<T, I, K> {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        let items = self.items.get(data);
+        let mut offset: f32 = 0.;
+        let mut cross: f32 = 0.;
+
+        for (child, item) in self.children.iter_mut().zip(items) {
+            let size = child.widget.layout(constraints.loosen(), ctx, item, env);
+
+            match self.axis {
+                Axis::Horizontal => {
+                    child.widget.offset = Offset::new(offset, 0.);
+                    offset += size.w + self.spacing;
+                    cross = cross.max(size.h);
+                }
+                Axis::Vertical => {
+                    child.widget.offset = Offset::new(0., offset);
+                    offset += size.h + self.spacing;
+                    cross = cross.max(size.w);
+                }
+            }
+        }
+        let main = (offset - self.spacing).max(0.);
+
+        self.size = constraints.constrain(match self.axis {
+            Axis::Horizontal => [main, cross],
+            Axis::Vertical => [cross, main],
+        });
+        self.size
+    }
+
+    fn paint(&mut self, mut canvas: Canvas<'_>, data: &T) {
+        let items = self.items.get(data);
+
+        for (child, item) in self.children.iter_mut().zip(items) {
+            let size = child.widget.size;
+            child.widget.paint(canvas.resize(size), item);
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<'_>, data: &T) {
+        let items = self.items.get(data);
+
+        if self.reconcile(items, ctx) {
+            ctx.request_layout();
+            ctx.request_paint();
+        }
+        for (child, item) in self.children.iter_mut().zip(items) {
+            let requests = RefCell::default();
+
+            child.widget.update(&ctx.requests(&requests), item);
+            ctx.lift_requests(requests, lift(&self.items, &self.key, &child.key));
+        }
+    }
+
+    fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        let items = self.items.get_mut(data);
+
+        for (child, item) in self.children.iter_mut().zip(items) {
+            // The items may have changed since the last update, in which case
+            // children that no longer match their item are skipped.
+            if child.key != (self.key)(item) {
+                continue;
+            }
+            let requests = RefCell::default();
+            let flow = child.widget.event(event, &ctx.requests(&requests), item);
+            ctx.lift_requests(requests, lift(&self.items, &self.key, &child.key));
+
+            flow?;
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn lifecycle(
+        &mut self,
+        lifecycle: &WidgetLifecycle<'_>,
+        ctx: &Context<'_>,
+        data: &T,
+        env: &Env,
+    ) {
+        let items = self.items.get(data);
+
+        for (child, item) in self.children.iter_mut().zip(items) {
+            let requests = RefCell::default();
+
+            child
+                .widget
+                .lifecycle(lifecycle, &ctx.requests(&requests), item, env);
+            ctx.lift_requests(requests, lift(&self.items, &self.key, &child.key));
+        }
+    }
+
+    fn frame(&mut self, surfaces: &Surfaces, data: &mut T) {
+        let items = self.items.get_mut(data);
+
+        for (child, item) in self.children.iter_mut().zip(items) {
+            if child.key == (self.key)(item) {
+                child.widget.frame(surfaces, item);
+            }
+        }
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.bounds().contains(point)
+    }
+
+    fn cursor(&self) -> Option<&'static str> {
+        self.children
+            .iter()
+            .find(|c| c.widget.hot)
+            .and_then(|c| c.widget.cursor())
+    }
+
+    fn display(&self) -> String {
+        format!("List({})", self.children.len())
+    }
+}
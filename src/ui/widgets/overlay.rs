@@ -0,0 +1,318 @@
+//! Overlay layer, for popups, tooltips and modals.
+//!
+//! Widgets show content in the overlay layer with [`Context::show_overlay`]. It's painted
+//! above the rest of the tree, isn't clipped by the widget that showed it, and receives
+//! events first.
+//!
+//! ```
+//! use std::ops::ControlFlow;
+//!
+//! use rgx::gfx::Rgba8;
+//! use rgx::ui::*;
+//! use rgx::Size;
+//!
+//! /// Shows a menu below itself when clicked, and hides it on the next click.
+//! struct MenuButton(Option<OverlayId>);
+//!
+//! impl Widget<()> for MenuButton {
+//!     fn layout(&mut self, c: BoxConstraints, _: &LayoutCtx<'_>, _: &(), _: &Env) -> Size {
+//!         c.constrain([64., 16.])
+//!     }
+//!
+//!     fn paint(&mut self, mut canvas: Canvas<'_>, _: &()) {
+//!         canvas.fill(canvas.bounds(), Rgba8::GREY);
+//!     }
+//!
+//!     fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, _: &mut ()) -> ControlFlow<()> {
+//!         if let WidgetEvent::MouseDown(_) = event {
+//!             match self.0.take() {
+//!                 Some(id) => ctx.hide_overlay(id),
+//!                 None => {
+//!                     let menu = Rgba8::WHITE.sized([64., 128.]);
+//!                     self.0 = Some(ctx.show_overlay::<()>(menu, [0., 16.]));
+//!                 }
+//!             }
+//!             return ControlFlow::Break(());
+//!         }
+//!         ControlFlow::Continue(())
+//!     }
+//! }
+//!
+//! // The UI root is wrapped in an overlay.
+//! let ui = Ui::new(MenuButton(None), ());
+//! ```
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::ops::ControlFlow;
+
+use crate::ui::*;
+
+/// A widget shown in the overlay layer.
+struct Layer<T> {
+    id: OverlayId,
+    /// Requested position, relative to the overlay.
+    anchor: Point,
+    /// Whether input to the widgets below is blocked.
+    modal: bool,
+    widget: Pod<T, Box<dyn Widget<T>>>,
+}
+
+/// Overlay layer. Hosts widgets shown with [`Context::show_overlay`] by its descendants,
+/// above its child. [`Ui`] wraps the root widget in an overlay.
+///
+/// Layers are positioned at their anchor, moved as needed to fit in the overlay.
+/// Events are delivered to the topmost layers first, and pointer events over a layer
+/// don't reach the widgets below it.
+pub struct Overlay<T> {
+    child: Pod<T, Box<dyn Widget<T>>>,
+    /// Layers, from bottom to top.
+    layers: Vec<Layer<T>>,
+    /// Layers shown since the last update. They're added on top of the others on the
+    /// next update, so that they're updated and laid out before receiving events.
+    shown: Vec<Layer<T>>,
+    /// Widgets of layers hidden while they had focus, until they're told they lost it.
+    hidden: Vec<Pod<T, Box<dyn Widget<T>>>>,
+    size: Size,
+}
+
+impl<T: 'static> Overlay<T> {
+    pub fn new(child: impl Widget<T> + 'static) -> Self {
+        Self {
+            child: Pod::new(Box::new(child)),
+            layers: Vec::new(),
+            shown: Vec::new(),
+            hidden: Vec::new(),
+            size: Size::ZERO,
+        }
+    }
+
+    /// Number of layers shown.
+    pub fn len(&self) -> usize {
+        self.layers.len() + self.shown.len()
+    }
+
+    /// Whether no layers are shown.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Handle overlay requests made by descendants, and pass on the other requests.
+    fn requests(&mut self, requests: RefCell<Requests>, ctx: &Context<'_>) {
+        let mut requests = requests.into_inner();
+        let mut overlays = VecDeque::from(std::mem::take(&mut requests.overlays));
+
+        while let Some(request) = overlays.pop_front() {
+            match request {
+                OverlayRequest::Show {
+                    id,
+                    anchor,
+                    widget,
+                    data,
+                    modal,
+                } => match widget.downcast::<Box<dyn Widget<T>>>() {
+                    Ok(widget) => self.shown.push(Layer {
+                        id,
+                        anchor: anchor.untransform(ctx.transform),
+                        modal,
+                        widget: Pod::new(*widget),
+                    }),
+                    Err(_) => {
+                        let expected = std::any::type_name::<T>();
+
+                        // This is a bug in the widget showing the overlay, see
+                        // `Context::show_overlay`.
+                        debug_assert!(
+                            false,
+                            "overlay {id} has data {data}, but the overlay layer has {expected}"
+                        );
+                        error!("ignoring overlay {id} with data {data}, expected {expected}");
+                        continue;
+                    }
+                },
+                OverlayRequest::Hide(id) => {
+                    let layer = if let Some(i) = self.layers.iter().position(|l| l.id == id) {
+                        self.layers.remove(i)
+                    } else if let Some(i) = self.shown.iter().position(|l| l.id == id) {
+                        self.shown.remove(i)
+                    } else {
+                        continue;
+                    };
+                    let mut widget = layer.widget;
+                    let hidden = RefCell::default();
+                    let inner = ctx.requests(&hidden);
+
+                    // A layer with focus is kept until it's told it lost it.
+                    if widget.is_focus_path(ctx) {
+                        widget.hide_overlays(&inner);
+                        self.hidden.push(widget);
+                    } else {
+                        widget.remove(&inner);
+                    }
+                    // Layers shown by the hidden layer are hidden along with it.
+                    let mut hidden = hidden.into_inner();
+                    overlays.extend(std::mem::take(&mut hidden.overlays));
+                    requests.merge(hidden);
+                }
+            }
+            ctx.request_layout();
+            ctx.request_paint();
+        }
+        if let Some(parent) = ctx.requests {
+            parent.borrow_mut().merge(requests);
+        }
+    }
+}
+
+impl<T: 'static> Widget<T> for Overlay<T> {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        self.size = constraints.constrain(self.child.layout(constraints, ctx, data, env));
+
+        for layer in &mut self.layers {
+            let size = layer
+                .widget
+                .layout(BoxConstraints::loose(self.size), ctx, data, env);
+            layer.widget.offset = Offset::new(
+                layer.anchor.x.min(self.size.w - size.w).max(0.),
+                layer.anchor.y.min(self.size.h - size.h).max(0.),
+            );
+        }
+        self.size
+    }
+
+    fn paint(&mut self, mut canvas: Canvas<'_>, data: &T) {
+        let size = self.child.size;
+        self.child.paint(canvas.resize(size), data);
+
+        for layer in &mut self.layers {
+            let size = layer.widget.size;
+            layer.widget.paint(canvas.resize(size), data);
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<'_>, data: &T) {
+        let requests = RefCell::default();
+        self.child.update(&ctx.requests(&requests), data);
+
+        self.requests(requests, ctx);
+
+        // Layers shown since the last update, including by the child, are updated
+        // before their first layout.
+        self.layers.append(&mut self.shown);
+
+        let requests = RefCell::default();
+        for layer in &mut self.layers {
+            layer.widget.update(&ctx.requests(&requests), data);
+        }
+        self.requests(requests, ctx);
+    }
+
+    fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        let requests = RefCell::default();
+        let inner = ctx.requests(&requests);
+        let mut flow = ControlFlow::Continue(());
+        // Whether the pointer is over a layer above, or a modal layer is above.
+        let mut covered = false;
+        let mut modal = false;
+
+        if let WidgetEvent::Focus(false) = event {
+            for mut widget in self.hidden.drain(..) {
+                let _ = widget.event(event, &inner, data);
+                widget.remove(&inner);
+            }
+        }
+        for layer in self.layers.iter_mut().rev() {
+            flow = forward(&mut layer.widget, event, &inner, data, covered, modal);
+
+            if flow.is_break() {
+                break;
+            }
+            covered |= layer.widget.hot;
+            modal |= layer.modal;
+        }
+        if flow.is_continue() {
+            flow = forward(&mut self.child, event, &inner, data, covered, modal);
+        }
+        self.requests(requests, ctx);
+
+        flow
+    }
+
+    fn lifecycle(
+        &mut self,
+        lifecycle: &WidgetLifecycle<'_>,
+        ctx: &Context<'_>,
+        data: &T,
+        env: &Env,
+    ) {
+        let requests = RefCell::default();
+        let inner = ctx.requests(&requests);
+
+        self.child.lifecycle(lifecycle, &inner, data, env);
+
+        for layer in &mut self.layers {
+            layer.widget.lifecycle(lifecycle, &inner, data, env);
+        }
+        self.requests(requests, ctx);
+    }
+
+    fn frame(&mut self, surfaces: &Surfaces, data: &mut T) {
+        self.child.frame(surfaces, data);
+
+        for layer in &mut self.layers {
+            layer.widget.frame(surfaces, data);
+        }
+    }
+
+    fn cursor(&self) -> Option<&'static str> {
+        match self.layers.iter().rev().find(|l| l.widget.hot) {
+            Some(layer) => layer.widget.cursor(),
+            None if self.child.hot => self.child.cursor(),
+            None => None,
+        }
+    }
+
+    fn display(&self) -> String {
+        format!("Overlay({}, {})", self.child.display(), self.layers.len())
+    }
+}
+
+/// Deliver an event to a widget below the topmost layers. Pointer events don't
+/// reach it if it's `covered` by a layer under the pointer, and neither pointer nor
+/// keyboard events reach it if there's a `modal` layer above it.
+fn forward<T>(
+    widget: &mut Pod<T, Box<dyn Widget<T>>>,
+    event: &WidgetEvent,
+    ctx: &Context<'_>,
+    data: &mut T,
+    covered: bool,
+    modal: bool,
+) -> ControlFlow<()> {
+    let blocked = covered || modal;
+
+    match event {
+        // Widgets that captured the pointer keep receiving moves.
+        WidgetEvent::MouseMove(_) if blocked && !widget.active => {
+            widget.event(&WidgetEvent::MouseExit, ctx, data)
+        }
+        WidgetEvent::MouseEnter | WidgetEvent::MouseDown(_) | WidgetEvent::MouseScroll(_)
+            if blocked =>
+        {
+            ControlFlow::Continue(())
+        }
+        WidgetEvent::KeyDown { .. }
+        | WidgetEvent::KeyUp { .. }
+        | WidgetEvent::CharacterReceived(..)
+        | WidgetEvent::Paste(_)
+            if modal =>
+        {
+            ControlFlow::Continue(())
+        }
+        _ => widget.event(event, ctx, data),
+    }
+}
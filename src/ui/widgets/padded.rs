@@ -0,0 +1,96 @@
+use std::ops::ControlFlow;
+
+use crate::ui::*;
+
+/// A container that adds padding around its child.
+pub struct Padded<T> {
+    widget: Pod<T, Box<dyn Widget<T>>>,
+    padding: Padding,
+    size: Size,
+}
+
+impl<T> Padded<T> {
+    pub fn new(widget: impl Widget<T> + 'static, padding: impl Into<Padding>) -> Self {
+        Self {
+            widget: Pod::new(Box::new(widget)),
+            padding: padding.into(),
+            size: Size::ZERO,
+        }
+    }
+
+    /// Set the padding.
+    pub fn padding(self, padding: impl Into<Padding>) -> Self {
+        Self {
+            padding: padding.into(),
+            ..self
+        }
+    }
+}
+
+impl<T> Widget<T> for Padded<T> {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        let Padding {
+            top,
+            right,
+            bottom,
+            left,
+        } = self.padding.clone();
+        let extra = Size::new(left + right, top + bottom);
+        let child = self
+            .widget
+            .layout(constraints.deflate(extra), ctx, data, env);
+
+        self.widget.offset = Offset::new(left, top);
+        self.size = constraints.constrain([child.w + extra.w, child.h + extra.h]);
+        self.size
+    }
+
+    fn paint(&mut self, mut canvas: Canvas<'_>, data: &T) {
+        let size = self.widget.size;
+        self.widget.paint(canvas.resize(size), data);
+    }
+
+    fn update(&mut self, ctx: &Context<'_>, data: &T) {
+        self.widget.update(ctx, data);
+    }
+
+    fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        self.widget.event(event, ctx, data)
+    }
+
+    fn lifecycle(
+        &mut self,
+        lifecycle: &WidgetLifecycle<'_>,
+        ctx: &Context<'_>,
+        data: &T,
+        env: &Env,
+    ) {
+        self.widget.lifecycle(lifecycle, ctx, data, env)
+    }
+
+    fn frame(&mut self, surfaces: &Surfaces, data: &mut T) {
+        self.widget.frame(surfaces, data);
+    }
+
+    fn cursor(&self) -> Option<&'static str> {
+        if self.widget.hot {
+            self.widget.cursor()
+        } else {
+            None
+        }
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        Rect::<f32>::origin(self.size).contains(point)
+    }
+
+    fn display(&self) -> String {
+        format!("Padded({})", self.widget.display())
+    }
+}
@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
 use std::ops::{ControlFlow, Deref};
 use std::{env, fmt};
 
@@ -11,7 +13,27 @@ pub struct Pod<T, W> {
     pub offset: Offset,
     pub hot: bool,
     pub active: bool,
+    /// Whether this widget is the target of pointer events, ie. it's the innermost
+    /// hot widget.
+    pub target: bool,
 
+    /// Drag in progress, if a button is held. Set on `MouseDown`.
+    drag: Option<Drag>,
+    /// Whether the pointer moved since the drag started.
+    dragging: bool,
+    /// Timers requested by this widget or its descendants, that haven't fired yet.
+    timers: HashSet<TimerToken>,
+    /// Overlays shown by this widget or its descendants, that haven't been hidden.
+    overlays: HashSet<OverlayId>,
+    /// Widgets in this subtree, excluding this one. Kept up to date as widgets are
+    /// added and removed, rather than collected on every update.
+    descendants: HashSet<WidgetId>,
+    /// Whether the widget was added to the tree, on its first update.
+    attached: bool,
+    /// Whether the widget received [`WidgetLifecycle::Initialized`].
+    initialized: bool,
+    /// Requests made while initializing during layout, passed on with the next update.
+    pending: Option<Requests>,
     widget: W,
     data: PhantomData<T>,
 }
@@ -24,13 +46,138 @@ impl<T, W: Widget<T>> Pod<T, W> {
             offset: Offset::ZERO,
             hot: false,
             active: false,
+            target: false,
+            drag: None,
+            dragging: false,
+            timers: HashSet::new(),
+            overlays: HashSet::new(),
+            descendants: HashSet::new(),
+            attached: false,
+            initialized: false,
+            pending: None,
             widget,
             data: PhantomData,
         }
     }
 
+    /// Handle requests made by the widget, and pass them on to the parent.
+    fn requests(&mut self, requests: RefCell<Requests>, parent: &Context<'_>) {
+        let requests = requests.into_inner();
+
+        if requests.is_empty() {
+            return;
+        }
+        self.timers
+            .extend(requests.timers.iter().map(|(token, _)| *token));
+
+        for request in &requests.overlays {
+            match request {
+                OverlayRequest::Show { id, .. } => self.overlays.insert(*id),
+                OverlayRequest::Hide(id) => self.overlays.remove(id),
+            };
+        }
+        for id in &requests.added {
+            if *id != self.id {
+                self.descendants.insert(*id);
+            }
+        }
+        for id in &requests.removed {
+            self.descendants.remove(id);
+        }
+
+        if let Some(parent) = parent.requests {
+            parent.borrow_mut().merge(requests);
+        }
+    }
+
+    /// Hide the overlays shown by this widget and its descendants, eg. when the
+    /// widget is hidden. Children that are dropped should be removed with
+    /// [`Pod::remove`] instead.
+    pub fn hide_overlays(&mut self, ctx: &Context<'_>) {
+        for id in self.overlays.drain() {
+            ctx.hide_overlay(id);
+        }
+    }
+
+    /// Remove this widget from the tree: hide the overlays shown by it and its
+    /// descendants, and stop routing events to them. Containers should call this
+    /// before dropping a child.
+    pub fn remove(&mut self, ctx: &Context<'_>) {
+        self.hide_overlays(ctx);
+
+        if let Some(requests) = ctx.requests {
+            let mut requests = requests.borrow_mut();
+
+            requests.removed.push(self.id);
+            requests.removed.extend(self.descendants.drain());
+        }
+        self.attached = false;
+    }
+
+    /// Whether the focused widget is in this subtree. If no widget has focus,
+    /// this is `false`, so that keyboard events aren't delivered.
+    pub(crate) fn is_focus_path(&self, ctx: &Context<'_>) -> bool {
+        ctx.focused().map_or(false, |id| self.in_subtree(id))
+    }
+
+    /// Whether the given widget is this one, or one of its descendants.
+    fn in_subtree(&self, id: WidgetId) -> bool {
+        id == self.id || self.descendants.contains(&id)
+    }
+
+    /// Deliver an event that propagates along a path: first to our widget's capture
+    /// handler, then to the widget itself, which forwards it to its descendants.
+    fn dispatch(
+        &mut self,
+        event: &WidgetEvent,
+        ctx: &Context<'_>,
+        data: &mut T,
+    ) -> ControlFlow<()> {
+        let target = match event {
+            WidgetEvent::KeyDown { .. }
+            | WidgetEvent::KeyUp { .. }
+            | WidgetEvent::CharacterReceived(..)
+            | WidgetEvent::Paste(_) => ctx.has_focus(),
+            _ => self.target,
+        };
+        self.widget
+            .capture(event, &ctx.phase(Phase::Capture), data)?;
+        self.widget.event(
+            event,
+            &ctx.phase(if target { Phase::Target } else { Phase::Bubble }),
+            data,
+        )
+    }
+
+    /// Update the drag in progress with the current cursor position, and deliver
+    /// the drag events to the widget.
+    fn drag(&mut self, cursor: Point, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        let Some(drag) = &mut self.drag else {
+            return ControlFlow::Continue(());
+        };
+        if cursor == drag.position {
+            return ControlFlow::Continue(());
+        }
+        drag.delta = cursor - drag.position;
+        drag.position = cursor;
+
+        let drag = *drag;
+
+        if !self.dragging {
+            self.dragging = true;
+            self.widget
+                .event(&WidgetEvent::DragStart(drag), ctx, data)?;
+        }
+        self.widget.event(&WidgetEvent::DragMove(drag), ctx, data)
+    }
+
     fn context<'a>(&self, parent: &'a Context<'_>) -> Context<'a> {
-        parent.offset(self.offset).hot(self.hot).active(self.active)
+        parent
+            .offset(self.offset)
+            .hot(self.hot)
+            .active(self.active)
+            .phase(Phase::Target)
+            .widget_id(self.id)
     }
 
     fn bounds(&self) -> Rect<f32> {
@@ -49,8 +196,36 @@ impl<T, W: Widget<T>> fmt::Display for Pod<T, W> {
 }
 
 impl<T, W: Widget<T>> Widget<T> for Pod<T, W> {
-    fn layout(&mut self, parent: Size, ctx: &LayoutCtx<'_>, data: &T, env: &Env) -> Size {
-        self.size = self.widget.layout(parent, ctx, data, env);
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        // Widgets created after startup, eg. by a parent widget in response to an
+        // event, are initialized before their first layout. Requests can't be passed
+        // on to our parent during layout, so they're kept for the next update.
+        if !self.initialized {
+            let surfaces = Surfaces::new();
+            let requests = RefCell::default();
+
+            self.lifecycle(
+                &WidgetLifecycle::Initialized(ctx.textures),
+                &Context::new(Point::ORIGIN, &surfaces).requests(&requests),
+                data,
+                env,
+            );
+            let requests = requests.into_inner();
+
+            if !requests.is_empty() {
+                self.pending
+                    .get_or_insert_with(Requests::default)
+                    .merge(requests);
+                ctx.request_layout();
+            }
+        }
+        self.size = constraints.constrain(self.widget.layout(constraints, ctx, data, env));
         self.size
     }
 
@@ -67,17 +242,44 @@ impl<T, W: Widget<T>> Widget<T> for Pod<T, W> {
     }
 
     fn update(&mut self, ctx: &Context<'_>, data: &T) {
-        self.widget.update(&self.context(ctx), data)
+        if let (Some(pending), Some(parent)) = (self.pending.take(), ctx.requests) {
+            parent.borrow_mut().merge(pending);
+        }
+        let requests = RefCell::<Requests>::default();
+        let inner = self.context(ctx).requests(&requests);
+
+        if self.widget.accepts_focus() {
+            let mut requests = requests.borrow_mut();
+
+            requests.focused = inner.has_focus();
+
+            if inner.focus_chain {
+                requests.focus_chain.push(self.id);
+            }
+        }
+        self.widget.update(&inner, data);
+
+        if !self.attached {
+            self.attached = true;
+            requests.borrow_mut().added.push(self.id);
+        }
+        self.requests(requests, ctx);
     }
 
     fn cursor(&self) -> Option<&'static str> {
         self.widget.cursor()
     }
 
-    fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
-        let ctx = self.context(ctx);
+    fn event(
+        &mut self,
+        event: &WidgetEvent,
+        parent: &Context<'_>,
+        data: &mut T,
+    ) -> ControlFlow<()> {
+        let requests = RefCell::default();
+        let ctx = self.context(parent).requests(&requests);
 
-        match event {
+        let flow = match event {
             WidgetEvent::MouseEnter => {
                 let contains =
                     self.bounds().contains(ctx.cursor) && self.widget.contains(ctx.cursor);
@@ -101,45 +303,159 @@ impl<T, W: Widget<T>> Widget<T> for Pod<T, W> {
                 let cursor = point.untransform(self.transform());
                 let contains = self.bounds().contains(cursor) && self.widget.contains(cursor);
 
-                if contains {
+                let flow = if contains {
                     // If the widget wasn't hot before, we send a `MouseEnter`.
                     if self.hot {
-                        self.widget
-                            .event(&WidgetEvent::MouseMove(cursor), &ctx, data)
+                        self.dispatch(&WidgetEvent::MouseMove(cursor), &ctx, data)
                     } else {
                         self.hot = true;
                         self.widget.event(&WidgetEvent::MouseEnter, &ctx, data)
                     }
                 } else if self.hot {
                     self.hot = false;
-                    self.widget.event(&WidgetEvent::MouseExit, &ctx, data)
+
+                    let flow = self.widget.event(&WidgetEvent::MouseExit, &ctx, data);
+
+                    // While active, the widget captures the pointer, and keeps
+                    // receiving moves outside of its bounds.
+                    if flow.is_continue() && self.active {
+                        self.dispatch(&WidgetEvent::MouseMove(cursor), &ctx, data)
+                    } else {
+                        flow
+                    }
+                } else if self.active {
+                    self.dispatch(&WidgetEvent::MouseMove(cursor), &ctx, data)
                 } else {
                     ControlFlow::Continue(())
+                };
+
+                if flow.is_continue() && self.active {
+                    self.drag(cursor, &ctx, data)
+                } else {
+                    flow
                 }
             }
-            WidgetEvent::MouseDown(_) => {
+            WidgetEvent::MouseDown(mouse) => {
                 // Only propagate event if hot.
                 if self.hot {
+                    let mouse = MouseEvent {
+                        position: mouse.position.untransform(self.transform()),
+                        ..*mouse
+                    };
                     self.active = true;
-                    self.widget.event(event, &ctx, data)
+                    self.drag = Some(Drag {
+                        button: mouse.button,
+                        start: mouse.position,
+                        position: mouse.position,
+                        delta: Vector::ZERO,
+                    });
+                    self.dragging = false;
+
+                    // Focus on click. Descendants override this request.
+                    if self.widget.accepts_focus() {
+                        ctx.request_focus();
+                    }
+                    self.dispatch(&WidgetEvent::MouseDown(mouse), &ctx, data)
                 } else {
                     ControlFlow::Continue(())
                 }
             }
-            WidgetEvent::MouseUp(_) => {
+            WidgetEvent::MouseUp(mouse) => {
                 // Only propagate event if active.
                 if self.active {
+                    let mouse = MouseEvent {
+                        position: mouse.position.untransform(self.transform()),
+                        ..*mouse
+                    };
                     // It may look wrong that we're setting active to `false`
                     // here while telling our widget that we're active, but
                     // it's not!
                     self.active = false;
+
+                    let flow = self.dispatch(&WidgetEvent::MouseUp(mouse), &ctx, data);
+                    let dragging = std::mem::take(&mut self.dragging);
+
+                    match self.drag.take() {
+                        Some(drag) if dragging && flow.is_continue() => {
+                            self.widget.event(&WidgetEvent::DragEnd(drag), &ctx, data)
+                        }
+                        _ => flow,
+                    }
+                } else {
+                    ControlFlow::Continue(())
+                }
+            }
+            WidgetEvent::MouseScroll(_) => {
+                if self.hot {
+                    self.dispatch(event, &ctx, data)
+                } else {
+                    ControlFlow::Continue(())
+                }
+            }
+            WidgetEvent::KeyDown { .. }
+            | WidgetEvent::KeyUp { .. }
+            | WidgetEvent::CharacterReceived(..)
+            | WidgetEvent::Paste(_) => {
+                // Only propagate along the path to the focused widget.
+                if self.is_focus_path(&ctx) {
+                    self.dispatch(event, &ctx, data)
+                } else {
+                    ControlFlow::Continue(())
+                }
+            }
+            WidgetEvent::Focus(_) => {
+                // The widget may have stopped accepting focus, or no longer be
+                // updated, but still has to be told it lost focus.
+                if ctx.focused().map_or(false, |id| self.in_subtree(id)) {
+                    self.widget.event(event, &ctx, data)
+                } else {
+                    ControlFlow::Continue(())
+                }
+            }
+            WidgetEvent::DragStart(_) | WidgetEvent::DragMove(_) | WidgetEvent::DragEnd(_) => {
+                // Drag events are generated by each pod, for its own widget.
+                ControlFlow::Continue(())
+            }
+            WidgetEvent::Route(cmd) => match cmd.target() {
+                Target::Widget(id) | Target::Subtree(id) if id != self.id => {
+                    // Keep routing towards the target, if it's in this subtree.
+                    if self.descendants.contains(&id) {
+                        self.widget.event(event, &ctx, data)
+                    } else {
+                        ControlFlow::Continue(())
+                    }
+                }
+                _ => self
+                    .widget
+                    .event(&WidgetEvent::Command(cmd.clone()), &ctx, data),
+            },
+            WidgetEvent::Command(cmd) => match cmd.target() {
+                // The command was delivered to an ancestor.
+                Target::Widget(id) if id != self.id => ControlFlow::Continue(()),
+                _ => self.widget.event(event, &ctx, data),
+            },
+            WidgetEvent::Timer(token) => {
+                // Only propagate to the widget that requested the timer.
+                if self.timers.remove(token) {
                     self.widget.event(event, &ctx, data)
                 } else {
                     ControlFlow::Continue(())
                 }
             }
             _ => self.widget.event(event, &ctx, data),
+        };
+
+        if let WidgetEvent::MouseEnter | WidgetEvent::MouseExit | WidgetEvent::MouseMove(_) = event
+        {
+            let mut requests = requests.borrow_mut();
+
+            // We're the target of pointer events if none of our descendants are hot.
+            self.target = self.hot && !requests.hot;
+            requests.hot |= self.hot;
         }
+        self.requests(requests, parent);
+
+        flow
     }
 
     fn lifecycle(
@@ -149,7 +465,14 @@ impl<T, W: Widget<T>> Widget<T> for Pod<T, W> {
         data: &T,
         env: &Env,
     ) {
-        self.widget.lifecycle(lifecycle, ctx, data, env)
+        let requests = RefCell::default();
+
+        match lifecycle {
+            WidgetLifecycle::Initialized(_) => self.initialized = true,
+        }
+        self.widget
+            .lifecycle(lifecycle, &ctx.requests(&requests), data, env);
+        self.requests(requests, ctx);
     }
 
     fn frame(&mut self, surfaces: &Surfaces, data: &mut T) {
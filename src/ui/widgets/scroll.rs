@@ -0,0 +1,328 @@
+//! Scroll container, showing a part of a larger child.
+//!
+//! ```
+//! use rgx::gfx::Rgba8;
+//! use rgx::ui::widgets::{Flex, Scroll};
+//! use rgx::ui::WidgetExt;
+//!
+//! // A column of layers, taller than the viewport.
+//! let mut layers = Flex::<()>::column();
+//! for _ in 0..64 {
+//!     layers.push(Rgba8::WHITE.sized([64., 16.]), 0.);
+//! }
+//! let panel = Scroll::vertical(layers).sized([64., 128.]);
+//! ```
+use std::ops::ControlFlow;
+
+use crate::gfx::{Axis, Rectangle};
+use crate::ui::*;
+
+/// Distance scrolled per line of scroll input.
+pub const SCROLL_STEP: f32 = 20.;
+/// Thickness of scrollbars.
+pub const SCROLLBAR_WIDTH: f32 = 6.;
+
+/// A scrollbar drag in progress.
+#[derive(Debug, Copy, Clone)]
+struct ScrollbarDrag {
+    axis: Axis,
+    /// Cursor position at the start of the drag.
+    cursor: Point,
+    /// Scroll offset at the start of the drag.
+    offset: Vector,
+}
+
+/// Scroll container. Its child is laid out without bounds on the scrolled axes,
+/// and shown through a viewport the size of the container, outside of which it
+/// isn't painted.
+pub struct Scroll<T> {
+    child: Pod<T, Box<dyn Widget<T>>>,
+    horizontal: bool,
+    vertical: bool,
+    scrollbars: bool,
+    /// Scroll offset, ie. the position of the viewport in the child.
+    offset: Vector,
+    /// Size of the viewport.
+    size: Size,
+    drag: Option<ScrollbarDrag>,
+}
+
+impl<T> Scroll<T> {
+    /// Create a container scrolling on both axes.
+    pub fn new(child: impl Widget<T> + 'static) -> Self {
+        Self {
+            child: Pod::new(Box::new(child)),
+            horizontal: true,
+            vertical: true,
+            scrollbars: true,
+            offset: Vector::ZERO,
+            size: Size::ZERO,
+            drag: None,
+        }
+    }
+
+    /// Create a container scrolling vertically.
+    pub fn vertical(child: impl Widget<T> + 'static) -> Self {
+        Self {
+            horizontal: false,
+            ..Self::new(child)
+        }
+    }
+
+    /// Create a container scrolling horizontally.
+    pub fn horizontal(child: impl Widget<T> + 'static) -> Self {
+        Self {
+            vertical: false,
+            ..Self::new(child)
+        }
+    }
+
+    /// Set whether scrollbars are shown.
+    pub fn scrollbars(self, scrollbars: bool) -> Self {
+        Self { scrollbars, ..self }
+    }
+
+    /// Get the scroll offset.
+    pub fn offset(&self) -> Vector {
+        self.offset
+    }
+
+    /// Get the size of the content.
+    pub fn content_size(&self) -> Size {
+        self.child.size
+    }
+
+    /// Scroll to the given offset. Returns whether the offset changed.
+    pub fn scroll_to(&mut self, offset: impl Into<Vector>) -> bool {
+        let offset = offset.into();
+        let max = self.max_offset();
+        let offset = Vector::new(
+            if self.horizontal { offset.x } else { 0. }.clamp(0., max.x),
+            if self.vertical { offset.y } else { 0. }.clamp(0., max.y),
+        );
+        if offset == self.offset {
+            return false;
+        }
+        self.offset = offset;
+        self.child.offset = Offset::new(-offset.x, -offset.y);
+
+        true
+    }
+
+    /// Scroll by the given amount. Returns whether the offset changed.
+    pub fn scroll_by(&mut self, delta: impl Into<Vector>) -> bool {
+        self.scroll_to(self.offset + delta.into())
+    }
+
+    /// Get the bounds of the viewport, in the container's coordinates. The content is
+    /// clipped to these bounds, and only receives pointer events within them.
+    pub fn bounds(&self) -> Rect<f32> {
+        Rect::origin(self.size)
+    }
+
+    /// Largest scroll offset, where the end of the content is in view.
+    fn max_offset(&self) -> Vector {
+        Vector::new(
+            (self.child.size.w - self.size.w).max(0.),
+            (self.child.size.h - self.size.h).max(0.),
+        )
+    }
+
+    /// Track and thumb of the scrollbar on the given axis, if it's shown.
+    fn scrollbar(&self, axis: Axis) -> Option<(Rect<f32>, Rect<f32>)> {
+        let content = self.child.size;
+        let (scrolls, content, viewport, offset) = match axis {
+            Axis::Horizontal => (self.horizontal, content.w, self.size.w, self.offset.x),
+            Axis::Vertical => (self.vertical, content.h, self.size.h, self.offset.y),
+        };
+        if !self.scrollbars || !scrolls || content <= viewport || viewport <= 0. {
+            return None;
+        }
+        let thumb = (viewport * viewport / content).max(SCROLLBAR_WIDTH);
+        let position = offset / (content - viewport) * (viewport - thumb);
+
+        Some(match axis {
+            Axis::Horizontal => {
+                let y = self.size.h - SCROLLBAR_WIDTH;
+                (
+                    Rect::new([0., y], [viewport, SCROLLBAR_WIDTH]),
+                    Rect::new([position, y], [thumb, SCROLLBAR_WIDTH]),
+                )
+            }
+            Axis::Vertical => {
+                let x = self.size.w - SCROLLBAR_WIDTH;
+                (
+                    Rect::new([x, 0.], [SCROLLBAR_WIDTH, viewport]),
+                    Rect::new([x, position], [SCROLLBAR_WIDTH, thumb]),
+                )
+            }
+        })
+    }
+
+    /// Axis of the scrollbar under the given point, if any.
+    fn scrollbar_at(&self, point: Point) -> Option<Axis> {
+        [Axis::Horizontal, Axis::Vertical]
+            .into_iter()
+            .find(|&axis| {
+                self.scrollbar(axis)
+                    .map_or(false, |(track, _)| track.contains(point))
+            })
+    }
+}
+
+impl<T> Widget<T> for Scroll<T> {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        let mut max = constraints.max;
+
+        if self.horizontal {
+            max.w = f32::INFINITY;
+        }
+        if self.vertical {
+            max.h = f32::INFINITY;
+        }
+        let content = self
+            .child
+            .layout(BoxConstraints::loose(max), ctx, data, env);
+
+        self.size = constraints.constrain(content);
+        // Keep the offset in range, eg. if the content shrunk.
+        self.scroll_by(Vector::ZERO);
+        self.size
+    }
+
+    fn paint(&mut self, mut canvas: Canvas<'_>, data: &T) {
+        let size = self.child.size;
+        let mut viewport = canvas.clip(self.bounds());
+
+        self.child.paint(viewport.resize(size), data);
+
+        for axis in [Axis::Horizontal, Axis::Vertical] {
+            if let Some((track, thumb)) = self.scrollbar(axis) {
+                let active = self.drag.map_or(false, |d| d.axis == axis);
+
+                canvas.paint(Rectangle::from(track).fill(Rgba8::BLACK.alpha(0x22)));
+                let alpha = if active { 0xdd } else { 0x99 };
+
+                canvas.paint(Rectangle::from(thumb).fill(Rgba8::GREY.alpha(alpha)));
+            }
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<'_>, data: &T) {
+        self.child.update(ctx, data);
+    }
+
+    fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        match event {
+            WidgetEvent::MouseDown(mouse) => {
+                if let Some(axis) = self.scrollbar_at(mouse.position) {
+                    self.drag = Some(ScrollbarDrag {
+                        axis,
+                        cursor: mouse.position,
+                        offset: self.offset,
+                    });
+                    ctx.request_paint();
+
+                    return ControlFlow::Break(());
+                }
+            }
+            WidgetEvent::MouseMove(point) => {
+                if let Some(drag) = self.drag {
+                    // Move the thumb with the cursor.
+                    let max = self.max_offset();
+                    let Some((track, thumb)) = self.scrollbar(drag.axis) else {
+                        return ControlFlow::Break(());
+                    };
+                    let offset = match drag.axis {
+                        Axis::Horizontal => {
+                            let range = (track.width() - thumb.width()).max(1.);
+                            let delta = (point.x - drag.cursor.x) / range * max.x;
+
+                            Vector::new(drag.offset.x + delta, self.offset.y)
+                        }
+                        Axis::Vertical => {
+                            let range = (track.height() - thumb.height()).max(1.);
+                            let delta = (point.y - drag.cursor.y) / range * max.y;
+
+                            Vector::new(self.offset.x, drag.offset.y + delta)
+                        }
+                    };
+                    if self.scroll_to(offset) {
+                        ctx.request_paint();
+                    }
+                    return ControlFlow::Break(());
+                }
+                // Parts of the child outside of the viewport, or under the scrollbars,
+                // can't be hovered, unless they captured the pointer.
+                if !self.child.active
+                    && (!self.bounds().contains(*point) || self.scrollbar_at(*point).is_some())
+                {
+                    return self.child.event(&WidgetEvent::MouseExit, ctx, data);
+                }
+            }
+            WidgetEvent::MouseUp(_) if self.drag.is_some() => {
+                self.drag = None;
+                ctx.request_paint();
+
+                return ControlFlow::Break(());
+            }
+            WidgetEvent::MouseScroll(delta) => {
+                // Nested scroll containers get to scroll first.
+                self.child.event(event, ctx, data)?;
+
+                let delta = Vector::new(delta.x as f32, delta.y as f32) * SCROLL_STEP;
+                // Scroll vertically with a vertical scroll input, even if we only
+                // scroll horizontally.
+                let delta = if self.vertical {
+                    delta
+                } else {
+                    Vector::new(delta.x + delta.y, 0.)
+                };
+                return if self.scroll_by(Vector::new(-delta.x, -delta.y)) {
+                    ctx.request_paint();
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                };
+            }
+            _ => {}
+        }
+        self.child.event(event, ctx, data)
+    }
+
+    fn lifecycle(
+        &mut self,
+        lifecycle: &WidgetLifecycle<'_>,
+        ctx: &Context<'_>,
+        data: &T,
+        env: &Env,
+    ) {
+        self.child.lifecycle(lifecycle, ctx, data, env);
+    }
+
+    fn frame(&mut self, surfaces: &Surfaces, data: &mut T) {
+        self.child.frame(surfaces, data);
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.bounds().contains(point)
+    }
+
+    fn cursor(&self) -> Option<&'static str> {
+        if self.child.hot {
+            self.child.cursor()
+        } else {
+            None
+        }
+    }
+
+    fn display(&self) -> String {
+        format!("Scroll({})", self.child.display())
+    }
+}
@@ -2,37 +2,54 @@ use std::ops::ControlFlow;
 
 use crate::ui::*;
 
-/// Just a container with a size.
+/// Just a container with a size. The child is forced to the given width and height,
+/// within the constraints of the parent. Dimensions that aren't set are left
+/// to the child.
 pub struct SizedBox<T> {
     widget: Pod<T, Box<dyn Widget<T>>>,
-    size: Size<f32>,
+    width: Option<f32>,
+    height: Option<f32>,
 }
 
 impl<T> SizedBox<T> {
     pub fn new(widget: impl Widget<T> + 'static) -> Self {
         Self {
             widget: Pod::new(Box::new(widget)),
-            size: Size::default(),
+            width: None,
+            height: None,
         }
     }
 
     /// Set container's width.
     pub fn width(mut self, width: f32) -> Self {
-        self.size.w = width;
+        self.width = Some(width);
         self
     }
 
     /// Set container's height.
     pub fn height(mut self, height: f32) -> Self {
-        self.size.h = height;
+        self.height = Some(height);
         self
     }
 }
 
 impl<T> Widget<T> for SizedBox<T> {
-    fn layout(&mut self, parent: Size, ctx: &LayoutCtx<'_>, data: &T, env: &Env) -> Size {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        let BoxConstraints { min, max } = constraints;
+        let w = self.width.map(|w| w.max(min.w).min(max.w));
+        let h = self.height.map(|h| h.max(min.h).min(max.h));
+
         self.widget.layout(
-            Size::new(self.size.w.min(parent.w), self.size.h.min(parent.h)),
+            BoxConstraints::new(
+                [w.unwrap_or(min.w), h.unwrap_or(min.h)],
+                [w.unwrap_or(max.w), h.unwrap_or(max.h)],
+            ),
             ctx,
             data,
             env,
@@ -66,14 +83,16 @@ impl<T> Widget<T> for SizedBox<T> {
     }
 
     fn contains(&self, point: Point) -> bool {
-        Rect::<f32>::origin(self.size).contains(point)
+        Rect::<f32>::origin(self.widget.size).contains(point)
     }
 
     fn display(&self) -> String {
+        let dimension = |n: Option<f32>| n.map_or(String::from("_"), |n| n.to_string());
+
         format!(
             "SizedBox[{}, {}]({})",
-            self.size.w,
-            self.size.h,
+            dimension(self.width),
+            dimension(self.height),
             self.widget.display()
         )
     }
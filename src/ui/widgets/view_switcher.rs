@@ -0,0 +1,150 @@
+use std::ops::ControlFlow;
+
+use crate::ui::*;
+
+/// Shows a child built from the data, which is rebuilt whenever a key derived
+/// from the data changes.
+///
+/// The new child is initialized before its first layout, like any widget created
+/// after startup. The child it replaces has its overlays hidden, and is told if it
+/// lost focus.
+pub struct ViewSwitcher<T, K> {
+    /// Get the key from the data.
+    key: Box<dyn Fn(&T) -> K>,
+    /// Build the child for a key.
+    build: Box<dyn Fn(&K, &T) -> Box<dyn Widget<T>>>,
+    /// The child, along with its key.
+    child: Option<(K, Pod<T, Box<dyn Widget<T>>>)>,
+    /// The child replaced while it had focus, until it's told it lost it.
+    replaced: Option<Pod<T, Box<dyn Widget<T>>>>,
+    /// Whether the child was rebuilt since the last pointer event.
+    rebuilt: bool,
+}
+
+impl<T, K: PartialEq> ViewSwitcher<T, K> {
+    pub fn new(
+        key: impl Fn(&T) -> K + 'static,
+        build: impl Fn(&K, &T) -> Box<dyn Widget<T>> + 'static,
+    ) -> Self {
+        Self {
+            key: Box::new(key),
+            build: Box::new(build),
+            child: None,
+            replaced: None,
+            rebuilt: false,
+        }
+    }
+
+    /// Get the key of the current child, if it was built.
+    pub fn current(&self) -> Option<&K> {
+        self.child.as_ref().map(|(key, _)| key)
+    }
+}
+
+impl<T, K: PartialEq> Widget<T> for ViewSwitcher<T, K> {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        match &mut self.child {
+            Some((_, child)) => child.layout(constraints, ctx, data, env),
+            None => constraints.smallest(),
+        }
+    }
+
+    fn paint(&mut self, mut canvas: Canvas<'_>, data: &T) {
+        if let Some((_, child)) = &mut self.child {
+            let size = child.size;
+            child.paint(canvas.resize(size), data);
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<'_>, data: &T) {
+        let key = (self.key)(data);
+
+        if self.current() != Some(&key) {
+            let child = Pod::new((self.build)(&key, data));
+
+            if let Some((_, mut old)) = self.child.replace((key, child)) {
+                // A child with focus is kept until it's told it lost it.
+                if old.is_focus_path(ctx) {
+                    old.hide_overlays(ctx);
+                    self.replaced = Some(old);
+                } else {
+                    old.remove(ctx);
+                }
+            }
+            self.rebuilt = true;
+
+            ctx.request_layout();
+            ctx.request_paint();
+        }
+        if let Some((_, child)) = &mut self.child {
+            child.update(ctx, data);
+        }
+    }
+
+    fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        if let WidgetEvent::Focus(false) = event {
+            if let Some(mut old) = self.replaced.take() {
+                let flow = old.event(event, ctx, data);
+                old.remove(ctx);
+                flow?;
+            }
+        }
+        let Some((_, child)) = &mut self.child else {
+            return ControlFlow::Continue(());
+        };
+        if let WidgetEvent::MouseEnter
+        | WidgetEvent::MouseExit
+        | WidgetEvent::MouseMove(_)
+        | WidgetEvent::MouseDown(_)
+        | WidgetEvent::MouseUp(_)
+        | WidgetEvent::MouseScroll(_) = event
+        {
+            if std::mem::take(&mut self.rebuilt) {
+                // Let the new child know whether it's under the cursor.
+                let _ = child.event(&WidgetEvent::MouseMove(ctx.cursor), ctx, data);
+            }
+        }
+        child.event(event, ctx, data)
+    }
+
+    fn lifecycle(
+        &mut self,
+        lifecycle: &WidgetLifecycle<'_>,
+        ctx: &Context<'_>,
+        data: &T,
+        env: &Env,
+    ) {
+        if let Some((_, child)) = &mut self.child {
+            child.lifecycle(lifecycle, ctx, data, env);
+        }
+    }
+
+    fn frame(&mut self, surfaces: &Surfaces, data: &mut T) {
+        if let Some((_, child)) = &mut self.child {
+            child.frame(surfaces, data);
+        }
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.child
+            .as_ref()
+            .map_or(false, |(_, child)| child.contains(point))
+    }
+
+    fn cursor(&self) -> Option<&'static str> {
+        self.child.as_ref().and_then(|(_, child)| child.cursor())
+    }
+
+    fn display(&self) -> String {
+        match &self.child {
+            Some((_, child)) => format!("ViewSwitcher({})", child.display()),
+            None => String::from("ViewSwitcher"),
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use std::ops::ControlFlow;
+
+use crate::ui::*;
+
+/// Vertical stack.
+pub struct VStack<T> {
+    size: Size,
+    spacing: f32,
+    children: Vec<Pod<T, Box<dyn Widget<T>>>>,
+}
+
+impl<T> Default for VStack<T> {
+    fn default() -> Self {
+        Self {
+            size: Size::default(),
+            spacing: 0.,
+            children: Vec::default(),
+        }
+    }
+}
+
+impl<T> VStack<T> {
+    pub fn new(children: Vec<Box<dyn Widget<T>>>) -> Self {
+        Self {
+            size: Size::default(),
+            spacing: 0.,
+            children: children.into_iter().map(Pod::new).collect(),
+        }
+    }
+
+    pub fn push(&mut self, child: impl Widget<T> + 'static) {
+        self.children.push(Pod::new(Box::new(child)));
+    }
+
+    pub fn child(mut self, child: impl Widget<T> + 'static) -> Self {
+        self.push(child);
+        self
+    }
+
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn bounds(&self) -> Rect<f32> {
+        Rect::origin(self.size)
+    }
+}
+
+impl<T> Widget<T> for VStack<T> {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        let mut offset = Vector::ZERO;
+        let mut width: f32 = 0.;
+
+        for widget in &mut self.children {
+            // Each child gets the space left over by the ones above it.
+            let available = constraints
+                .loosen()
+                .max_height((constraints.max.h - offset.y).max(0.));
+
+            widget.layout(available, ctx, data, env);
+            widget.offset = offset;
+            width = width.max(widget.size.w);
+            offset.y += widget.size.h + self.spacing;
+        }
+        self.size = constraints.constrain([width, (offset.y - self.spacing).max(0.)]);
+        self.size
+    }
+
+    fn paint(&mut self, mut canvas: Canvas<'_>, data: &T) {
+        for widget in &mut self.children {
+            let size = widget.size;
+            widget.paint(canvas.resize(size), data);
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<'_>, data: &T) {
+        for widget in &mut self.children {
+            widget.update(ctx, data);
+        }
+    }
+
+    fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        for widget in &mut self.children {
+            if let flow @ ControlFlow::Break(_) = widget.event(event, ctx, data) {
+                return flow;
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn lifecycle(
+        &mut self,
+        lifecycle: &WidgetLifecycle<'_>,
+        ctx: &Context<'_>,
+        data: &T,
+        env: &Env,
+    ) {
+        for widget in &mut self.children {
+            widget.lifecycle(lifecycle, ctx, data, env);
+        }
+    }
+
+    fn frame(&mut self, surfaces: &Surfaces, data: &mut T) {
+        for widget in &mut self.children {
+            widget.frame(surfaces, data);
+        }
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.bounds().contains(point)
+    }
+
+    fn cursor(&self) -> Option<&'static str> {
+        for widget in &self.children {
+            if widget.hot {
+                return widget.cursor();
+            }
+        }
+        None
+    }
+
+    fn display(&self) -> String {
+        format!("VStack({})", self.children.len())
+    }
+}
+
+pub fn vstack<W, T>(children: W) -> VStack<T>
+where
+    W: WidgetTuple<T>,
+{
+    VStack::new(children.to_vec())
+}
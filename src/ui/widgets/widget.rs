@@ -4,7 +4,7 @@ use crate::gfx::*;
 use crate::platform;
 use crate::ui::*;
 
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct WidgetId(Id);
 
 impl WidgetId {
@@ -23,17 +23,85 @@ impl fmt::Display for WidgetId {
     }
 }
 
+/// A mouse button press or release.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MouseEvent {
+    /// Button pressed or released.
+    pub button: platform::MouseButton,
+    /// Cursor position, in widget coordinates.
+    pub position: Point,
+    /// Keyboard modifiers held.
+    pub modifiers: platform::ModifiersState,
+    /// Number of consecutive clicks, eg. `2` for a double-click.
+    pub count: u32,
+}
+
+impl MouseEvent {
+    /// Create a new single-click mouse event, without modifiers.
+    pub fn new(button: platform::MouseButton, position: Point) -> Self {
+        Self {
+            button,
+            position,
+            modifiers: platform::ModifiersState::default(),
+            count: 1,
+        }
+    }
+
+    /// Set the keyboard modifiers.
+    pub fn modifiers(self, modifiers: platform::ModifiersState) -> Self {
+        Self { modifiers, ..self }
+    }
+
+    /// Set the click count.
+    pub fn count(self, count: u32) -> Self {
+        Self { count, ..self }
+    }
+}
+
+/// A drag gesture, in widget coordinates.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Drag {
+    /// Button held during the drag.
+    pub button: platform::MouseButton,
+    /// Where the drag started.
+    pub start: Point,
+    /// Current pointer position.
+    pub position: Point,
+    /// Movement since the last drag event.
+    pub delta: Vector,
+}
+
+impl Drag {
+    /// Total movement since the start of the drag.
+    pub fn offset(&self) -> Vector {
+        self.position - self.start
+    }
+}
+
 /// Widget event.
 #[derive(Debug, Clone)]
 pub enum WidgetEvent {
-    MouseDown(platform::MouseButton),
-    MouseUp(platform::MouseButton),
+    MouseDown(MouseEvent),
+    MouseUp(MouseEvent),
     MouseScroll(platform::LogicalDelta),
     MouseMove(Point),
     Resized(Size),
     MouseEnter,
     MouseExit,
+    /// The pointer started moving while a button is held over the widget.
+    /// Drag events are sent to each widget being dragged by its [`Pod`],
+    /// so containers don't need to forward them.
+    DragStart(Drag),
+    /// The pointer moved during a drag. The widget keeps receiving these when
+    /// the pointer leaves its bounds.
+    DragMove(Drag),
+    /// The button was released, ending the drag.
+    DragEnd(Drag),
+    /// The widget gained or lost keyboard focus. This is sent along the path to
+    /// the widget, for which [`Context::has_focus`] is `true`.
     Focus(bool),
+    /// The window gained or lost focus.
+    WindowFocus(bool),
     KeyDown {
         key: platform::Key,
         modifiers: platform::ModifiersState,
@@ -46,15 +114,30 @@ pub enum WidgetEvent {
     CharacterReceived(char, platform::ModifiersState),
     Paste(Option<String>),
     Tick(time::Duration),
+    /// A timer requested with [`Context::request_timer`] has fired.
+    Timer(TimerToken),
+    /// A command was received.
+    Command(Command),
+    /// A command being routed to its target. Only widgets containing the target
+    /// receive these, and should forward them to their children, like other events.
+    #[doc(hidden)]
+    Route(Command),
     Frame,
 }
 
 /// A UI widget that can be painted on screen.
 #[allow(unused_variables)]
 pub trait Widget<T> {
-    /// Compute the widget layout.
-    fn layout(&mut self, parent: Size, ctx: &LayoutCtx<'_>, data: &T, env: &Env) -> Size {
-        parent
+    /// Compute the widget layout. The returned size should satisfy the constraints.
+    /// By default, widgets take up as much space as they're allowed.
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        constraints.biggest()
     }
 
     /// Paint the widget.
@@ -63,7 +146,19 @@ pub trait Widget<T> {
     /// Update the widget's appearance.
     fn update(&mut self, ctx: &Context<'_>, data: &T) {}
 
-    /// Process an external event.
+    /// Process an event on its way down to its target, before any descendant
+    /// sees it. Only called for pointer and keyboard events. Returning
+    /// [`ControlFlow::Break`] stops the event from propagating further.
+    ///
+    /// Containers should not forward this to their children, since [`Pod`] takes
+    /// care of calling it on every widget along the event path.
+    fn capture(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Process an external event. Containers should forward events to their
+    /// children before handling them, so that events bubble up from their target.
+    /// See [`Phase`].
     fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
         ControlFlow::Continue(())
     }
@@ -86,6 +181,12 @@ pub trait Widget<T> {
         None
     }
 
+    /// Whether this widget can receive keyboard focus. Focusable widgets are cycled
+    /// through with `Tab`, and keyboard events are only sent to the focused widget.
+    fn accepts_focus(&self) -> bool {
+        false
+    }
+
     /// Check whether this widget contains a point.
     fn contains(&self, point: Point) -> bool {
         // The `Pod` around the widget will do a preliminary bounds check, so unless the widget
@@ -114,8 +215,14 @@ pub trait Widget<T> {
 }
 
 impl<T> Widget<T> for Box<dyn Widget<T>> {
-    fn layout(&mut self, parent: Size, ctx: &LayoutCtx<'_>, data: &T, env: &Env) -> Size {
-        self.deref_mut().layout(parent, ctx, data, env)
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        self.deref_mut().layout(constraints, ctx, data, env)
     }
 
     fn update(&mut self, ctx: &Context<'_>, data: &T) {
@@ -126,6 +233,10 @@ impl<T> Widget<T> for Box<dyn Widget<T>> {
         self.deref_mut().paint(canvas, data)
     }
 
+    fn capture(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
+        self.deref_mut().capture(event, ctx, data)
+    }
+
     fn event(&mut self, event: &WidgetEvent, ctx: &Context<'_>, data: &mut T) -> ControlFlow<()> {
         self.deref_mut().event(event, ctx, data)
     }
@@ -148,6 +259,10 @@ impl<T> Widget<T> for Box<dyn Widget<T>> {
         self.deref().cursor()
     }
 
+    fn accepts_focus(&self) -> bool {
+        self.deref().accepts_focus()
+    }
+
     fn contains(&self, point: Point) -> bool {
         self.deref().contains(point)
     }
@@ -211,6 +326,12 @@ pub trait WidgetExt<T>: Sized + Widget<T> + 'static {
     fn boxed(self) -> Box<dyn Widget<T> + 'static>;
     /// Size a widget.
     fn sized<S: Into<Size>>(self, size: S) -> widgets::SizedBox<T>;
+    /// Add padding around a widget.
+    fn padding(self, padding: impl Into<Padding>) -> widgets::Padded<T>;
+    /// Paint a background behind a widget.
+    fn background(self, background: impl Into<Fill>) -> widgets::Container<T>;
+    /// Use a widget of `T` in a tree of widgets of `S`, through a lens.
+    fn lens<S, L: Lens<S, T>>(self, lens: L) -> widgets::LensWrap<S, T, L, Self>;
 }
 
 impl<T, W: 'static> WidgetExt<T> for W
@@ -225,4 +346,16 @@ where
         let size = size.into();
         widgets::SizedBox::new(self).width(size.w).height(size.h)
     }
+
+    fn padding(self, padding: impl Into<Padding>) -> widgets::Padded<T> {
+        widgets::Padded::new(self, padding)
+    }
+
+    fn background(self, background: impl Into<Fill>) -> widgets::Container<T> {
+        widgets::Container::new(self).background(background)
+    }
+
+    fn lens<S, L: Lens<S, T>>(self, lens: L) -> widgets::LensWrap<S, T, L, Self> {
+        widgets::LensWrap::new(self, lens)
+    }
 }
@@ -28,11 +28,17 @@ impl<T> Widget<T> for ZStack<T> {
         }
     }
 
-    fn layout(&mut self, parent: Size, ctx: &LayoutCtx<'_>, data: &T, env: &Env) -> Size {
+    fn layout(
+        &mut self,
+        constraints: BoxConstraints,
+        ctx: &LayoutCtx<'_>,
+        data: &T,
+        env: &Env,
+    ) -> Size {
         for widget in &mut self.widgets {
-            widget.layout(parent, ctx, data, env);
+            widget.layout(constraints.loosen(), ctx, data, env);
         }
-        parent
+        constraints.biggest()
     }
 
     fn paint(&mut self, mut canvas: Canvas<'_>, data: &T) {
@@ -52,11 +58,13 @@ impl<T> Widget<T> for ZStack<T> {
         for widget in self.widgets.iter_mut().rev() {
             match event {
                 WidgetEvent::MouseMove(point) => {
-                    if widget.contains(*point) {
+                    // Only the topmost widget under the cursor is hot, but active widgets
+                    // capture the pointer, and receive all moves.
+                    if hot.is_none() && widget.contains(*point) {
                         flow = widget.event(event, ctx, data);
                         hot = Some(widget.id);
-
-                        break;
+                    } else if widget.active {
+                        flow = widget.event(event, ctx, data);
                     }
                 }
                 _ => {